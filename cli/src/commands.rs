@@ -0,0 +1,159 @@
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use shitcoin_raffle_client::accounts::ENTRANT_RAFFLE_OFFSET;
+use shitcoin_raffle_client::{instructions, pda, AdminSettings, Entrant, Raffle};
+use solana_sdk::signature::{Keypair, Signer};
+
+use crate::config::{parse_pubkey, RaffleConfig};
+use crate::rpc::Client;
+
+// Number of set_reward instructions packed into one transaction
+const REWARDS_PER_TRANSACTION: usize = 5;
+
+
+/*
+ * Program config
+ */
+
+pub fn init_admin(client: &Client, admin: &str) -> Result<()> {
+    let admin = parse_pubkey("admin", admin)?;
+    client.send(&[instructions::init_admin(&client.pubkey(), &admin)])
+}
+
+pub fn set_admin(client: &Client, admin: &str) -> Result<()> {
+    let admin = parse_pubkey("admin", admin)?;
+    client.send(&[instructions::propose_admin(&client.pubkey(), &admin)])
+}
+
+pub fn accept_admin(client: &Client) -> Result<()> {
+    client.send(&[instructions::accept_admin(&client.pubkey())])
+}
+
+
+/*
+ * Raffles
+ */
+
+fn load_config(path: Option<&Path>, flags: RaffleConfig) -> Result<RaffleConfig> {
+    match path {
+        Some(path) => Ok(RaffleConfig::from_file(path)?.merge(flags)),
+        None => Ok(flags),
+    }
+}
+
+fn now() -> Result<i64> {
+    let elapsed = SystemTime::now().duration_since(UNIX_EPOCH).context("reading system time")?;
+    Ok(elapsed.as_secs() as i64)
+}
+
+pub fn validate_raffle(path: Option<&Path>, flags: RaffleConfig) -> Result<()> {
+    let config = load_config(path, flags)?;
+    config.validate(now()?)?;
+
+    println!("raffle definition is valid");
+    Ok(())
+}
+
+pub fn create_raffle(client: &Client, path: Option<&Path>, flags: RaffleConfig) -> Result<()> {
+    let config = load_config(path, flags)?;
+    let params = config.validate(now()?)?;
+
+    let seed = config.seed()?.unwrap_or_else(|| Keypair::new().pubkey());
+    let currency = config.currency()?;
+    let reward = config.reward()?;
+
+    let admin_settings: AdminSettings = client.account(&pda::admin_settings().0)?;
+
+    println!("raffle {}", pda::raffle(&seed).0);
+    client.send(&[instructions::init_raffle(
+        &seed,
+        &currency,
+        &reward,
+        &admin_settings.get_treasury(),
+        &client.pubkey(),
+        &params,
+    )])
+}
+
+pub fn list_raffles(client: &Client) -> Result<()> {
+    let mut raffles = client.program_accounts::<Raffle>(None)?;
+    raffles.sort_by_key(|(_, raffle)| raffle.start_timestamp);
+
+    for (address, raffle) in raffles {
+        println!(
+            "{} status={:?} creator={} price={} tickets_sold={} rewards={}/{} start={} end={}",
+            address,
+            raffle.status,
+            raffle.creator,
+            raffle.price,
+            raffle.tickets_sold,
+            raffle.rewards_awarded,
+            raffle.rewards_num,
+            raffle.start_timestamp,
+            raffle.end_timestamp,
+        );
+    }
+
+    Ok(())
+}
+
+pub fn list_entrants(client: &Client, raffle: &str) -> Result<()> {
+    let raffle = parse_pubkey("raffle", raffle)?;
+    let mut entrants = client.program_accounts::<Entrant>(Some((ENTRANT_RAFFLE_OFFSET, &raffle)))?;
+    entrants.sort_by_key(|(_, entrant)| std::cmp::Reverse(entrant.tickets));
+
+    for (address, entrant) in entrants {
+        println!(
+            "{} user={} tickets={} rewards={}",
+            address,
+            entrant.user,
+            entrant.tickets,
+            entrant.rewards,
+        );
+    }
+
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct RewardRow {
+    user: String,
+    amount: u64,
+}
+
+pub fn set_rewards(client: &Client, raffle: &str, csv_path: &Path) -> Result<()> {
+    let raffle = parse_pubkey("raffle", raffle)?;
+
+    let mut reader = csv::Reader::from_path(csv_path)
+        .with_context(|| format!("reading {}", csv_path.display()))?;
+
+    let mut ixs = Vec::new();
+    for row in reader.deserialize() {
+        let row: RewardRow = row.with_context(|| format!("parsing {}", csv_path.display()))?;
+        let user = parse_pubkey("user", &row.user)?;
+        ixs.push(instructions::set_reward(&raffle, &client.pubkey(), &user, row.amount));
+    }
+
+    for chunk in ixs.chunks(REWARDS_PER_TRANSACTION) {
+        client.send(chunk)?;
+    }
+
+    Ok(())
+}
+
+pub fn claim_proceeds(client: &Client, raffle: &str) -> Result<()> {
+    let address = parse_pubkey("raffle", raffle)?;
+    let raffle: Raffle = client.account(&address)?;
+
+    client.send(&[instructions::claim_proceeds(&address, &raffle.currency, &raffle.creator, &client.pubkey())])
+}
+
+pub fn close_raffle(client: &Client, raffle: &str) -> Result<()> {
+    let address = parse_pubkey("raffle", raffle)?;
+    let raffle: Raffle = client.account(&address)?;
+
+    client.send(&[instructions::close_raffle(&address, &raffle.currency, &raffle.creator, &client.pubkey())])
+}
@@ -0,0 +1,276 @@
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context, Result};
+use clap::Args;
+use serde::Deserialize;
+use shitcoin_raffle_client::instructions::RaffleParams;
+use solana_sdk::pubkey::Pubkey;
+
+
+/*
+ * Raffle definition, read from a TOML or JSON file and / or command line flags
+ */
+
+#[derive(Args, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RaffleConfig {
+
+    /// Seed used in deriving the raffle account, random if not set
+    #[arg(long)]
+    pub seed: Option<String>,
+
+    /// Currency mint
+    #[arg(long)]
+    pub currency: Option<String>,
+
+    /// Reward mint, its mint authority must be the raffle account
+    #[arg(long)]
+    pub reward: Option<String>,
+
+    /// Entry price of the raffle
+    #[arg(long)]
+    pub price: Option<u64>,
+
+    /// Non-refundable fee
+    #[arg(long)]
+    pub fee: Option<u64>,
+
+    /// Number of rewards
+    #[arg(long)]
+    pub rewards_num: Option<u64>,
+
+    /// Reward amount
+    #[arg(long)]
+    pub rewards_amount: Option<u64>,
+
+    /// Raffle start timestamp
+    #[arg(long)]
+    pub start_timestamp: Option<i64>,
+
+    /// Raffle end timestamp
+    #[arg(long)]
+    pub end_timestamp: Option<i64>,
+
+    /// Number of tickets
+    #[arg(long)]
+    pub tickets: Option<u64>,
+
+    /// Maximum number of tickets that can be purchased by single user
+    #[arg(long)]
+    pub limit: Option<u64>,
+
+    /// Share of the fee paid to referrers, in basis points
+    #[arg(long)]
+    pub referral_share: Option<u16>,
+
+    /// Purchases within this many seconds before the end extend the raffle
+    #[arg(long)]
+    pub extension_window: Option<i64>,
+
+    /// Number of seconds the raffle is extended by on a late purchase
+    #[arg(long)]
+    pub extension_duration: Option<i64>,
+
+    /// Number of seconds late purchases can extend the raffle past its initial end timestamp
+    #[arg(long)]
+    pub max_auto_extension: Option<i64>,
+
+    /// Number of seconds entrants have to claim after the rewards are set
+    #[arg(long)]
+    pub claim_period: Option<i64>,
+}
+
+
+impl RaffleConfig {
+
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("reading {}", path.display()))?;
+
+        let json = path.extension().and_then(|ext| ext.to_str()) == Some("json");
+        Self::parse(&contents, json).with_context(|| format!("parsing {}", path.display()))
+    }
+
+    // TOML unless `json` is set
+    fn parse(contents: &str, json: bool) -> Result<Self> {
+        match json {
+            true => Ok(serde_json::from_str(contents)?),
+            false => Ok(toml::from_str(contents)?),
+        }
+    }
+
+    // Values set in `other` take precedence
+    pub fn merge(self, other: RaffleConfig) -> Self {
+        RaffleConfig {
+            seed: other.seed.or(self.seed),
+            currency: other.currency.or(self.currency),
+            reward: other.reward.or(self.reward),
+            price: other.price.or(self.price),
+            fee: other.fee.or(self.fee),
+            rewards_num: other.rewards_num.or(self.rewards_num),
+            rewards_amount: other.rewards_amount.or(self.rewards_amount),
+            start_timestamp: other.start_timestamp.or(self.start_timestamp),
+            end_timestamp: other.end_timestamp.or(self.end_timestamp),
+            tickets: other.tickets.or(self.tickets),
+            limit: other.limit.or(self.limit),
+            referral_share: other.referral_share.or(self.referral_share),
+            extension_window: other.extension_window.or(self.extension_window),
+            extension_duration: other.extension_duration.or(self.extension_duration),
+            max_auto_extension: other.max_auto_extension.or(self.max_auto_extension),
+            claim_period: other.claim_period.or(self.claim_period),
+        }
+    }
+
+    pub fn seed(&self) -> Result<Option<Pubkey>> {
+        self.seed.as_deref().map(|seed| parse_pubkey("seed", seed)).transpose()
+    }
+
+    pub fn currency(&self) -> Result<Pubkey> {
+        parse_pubkey("currency", required("currency", self.currency.as_deref())?)
+    }
+
+    pub fn reward(&self) -> Result<Pubkey> {
+        parse_pubkey("reward", required("reward", self.reward.as_deref())?)
+    }
+
+    pub fn params(&self) -> Result<RaffleParams> {
+        Ok(RaffleParams {
+            price: required("price", self.price)?,
+            rewards_num: required("rewards_num", self.rewards_num)?,
+            rewards_amount: required("rewards_amount", self.rewards_amount)?,
+            start_timestamp: required("start_timestamp", self.start_timestamp)?,
+            end_timestamp: required("end_timestamp", self.end_timestamp)?,
+            fee: self.fee,
+            tickets: self.tickets,
+            limit: self.limit,
+            referral_share: self.referral_share,
+            extension_window: self.extension_window,
+            extension_duration: self.extension_duration,
+            max_auto_extension: self.max_auto_extension,
+            claim_period: self.claim_period,
+        })
+    }
+}
+
+
+/*
+ * Offline validation, runs the same checks as the program
+ */
+
+impl RaffleConfig {
+
+    pub fn validate(&self, now: i64) -> Result<RaffleParams> {
+        self.seed()?;
+        self.currency()?;
+        self.reward()?;
+
+        let params = self.params()?;
+        params.validate(now).map_err(|err| anyhow!("invalid raffle: {}", err))?;
+
+        Ok(params)
+    }
+}
+
+
+fn required<T>(name: &str, value: Option<T>) -> Result<T> {
+    value.ok_or_else(|| anyhow!("missing raffle parameter `{}`", name))
+}
+
+pub fn parse_pubkey(name: &str, value: &str) -> Result<Pubkey> {
+    Pubkey::from_str(value).with_context(|| format!("invalid pubkey for `{}`: {}", name, value))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOML: &str = r#"
+        currency = "So11111111111111111111111111111111111111112"
+        price = 1_000
+        fee = 100
+        rewards_num = 2
+        rewards_amount = 50
+        start_timestamp = 100
+        end_timestamp = 200
+        limit = 5
+    "#;
+
+    // Flags a user would pass along with a config file
+    fn flags() -> RaffleConfig {
+        RaffleConfig {
+            reward: Some(Pubkey::new_unique().to_string()),
+            price: Some(2_000),
+            end_timestamp: Some(300),
+            ..RaffleConfig::default()
+        }
+    }
+
+    #[test]
+    fn toml_and_json_parse_to_the_same_config() {
+        let json = r#"{
+            "currency": "So11111111111111111111111111111111111111112",
+            "price": 1000,
+            "fee": 100,
+            "rewards_num": 2,
+            "rewards_amount": 50,
+            "start_timestamp": 100,
+            "end_timestamp": 200,
+            "limit": 5
+        }"#;
+
+        let toml = RaffleConfig::parse(TOML, false).unwrap();
+        let json = RaffleConfig::parse(json, true).unwrap();
+
+        assert_eq!(toml.params().unwrap(), json.params().unwrap());
+        assert_eq!(toml.currency().unwrap(), json.currency().unwrap());
+        assert_eq!(toml.params().unwrap().limit, Some(5));
+        assert_eq!(toml.tickets, None);
+    }
+
+    #[test]
+    fn unknown_fields_are_rejected() {
+        assert!(RaffleConfig::parse("prize = 1", false).is_err());
+        assert!(RaffleConfig::parse(r#"{ "prize": 1 }"#, true).is_err());
+    }
+
+    #[test]
+    fn flags_take_precedence_over_the_file() {
+        let flags = flags();
+        let reward = flags.reward.clone();
+        let config = RaffleConfig::parse(TOML, false).unwrap().merge(flags);
+
+        assert_eq!(config.reward, reward);
+        assert_eq!(config.price, Some(2_000));
+        assert_eq!(config.end_timestamp, Some(300));
+        assert_eq!(config.start_timestamp, Some(100));
+        assert_eq!(config.limit, Some(5));
+
+        config.validate(0).unwrap();
+    }
+
+    #[test]
+    fn missing_parameters_are_named() {
+        let config = RaffleConfig { price: None, ..RaffleConfig::parse(TOML, false).unwrap() };
+
+        let err = config.params().unwrap_err();
+        assert!(err.to_string().contains("`price`"), "{}", err);
+    }
+
+    #[test]
+    fn example_is_valid_once_mints_and_timestamps_are_set() {
+        let example = RaffleConfig::parse(include_str!("../raffle.example.toml"), false).unwrap();
+        assert!(example.validate(0).is_err());
+
+        let flags = RaffleConfig {
+            currency: Some(Pubkey::new_unique().to_string()),
+            reward: Some(Pubkey::new_unique().to_string()),
+            start_timestamp: Some(1_000),
+            end_timestamp: Some(2_000),
+            ..RaffleConfig::default()
+        };
+        example.merge(flags).validate(0).unwrap();
+    }
+}
@@ -0,0 +1,130 @@
+mod commands;
+mod config;
+mod rpc;
+
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Result};
+use clap::{Parser, Subcommand};
+use shitcoin_raffle_client::rpc::keypair_path;
+use solana_sdk::signature::read_keypair_file;
+
+use config::RaffleConfig;
+use rpc::Client;
+
+
+#[derive(Parser)]
+#[command(name = "raffle-cli", about = "Administer the shitcoin_raffle program")]
+struct Cli {
+
+    /// RPC endpoint, defaults to a local solana-test-validator
+    #[arg(long, short = 'u', global = true, default_value = "http://127.0.0.1:8899")]
+    url: String,
+
+    /// Keypair signing and paying for the transactions
+    #[arg(long, short = 'k', global = true, default_value = "~/.config/solana/id.json")]
+    keypair: String,
+
+    /// Simulate the transactions instead of sending them
+    #[arg(long, global = true)]
+    dry_run: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+
+    /// Create the AdminSettings account, signed by the program's upgrade authority
+    InitAdmin {
+        #[arg(long)]
+        admin: String,
+    },
+
+    /// Propose a new admin, who then has to run accept-admin
+    SetAdmin {
+        #[arg(long)]
+        admin: String,
+    },
+
+    /// Accept the admin role proposed to the keypair
+    AcceptAdmin,
+
+    /// Create a raffle from a TOML or JSON file and / or flags, flags take precedence
+    CreateRaffle {
+        #[arg(long)]
+        config: Option<PathBuf>,
+
+        #[command(flatten)]
+        raffle: RaffleConfig,
+    },
+
+    /// Check a raffle definition without sending anything
+    ValidateRaffle {
+        #[arg(long)]
+        config: Option<PathBuf>,
+
+        #[command(flatten)]
+        raffle: RaffleConfig,
+    },
+
+    /// List all raffles
+    ListRaffles,
+
+    /// List the entrants of a raffle
+    ListEntrants {
+        #[arg(long)]
+        raffle: String,
+    },
+
+    /// Set rewards from a CSV file with `user,amount` rows
+    SetRewards {
+        #[arg(long)]
+        raffle: String,
+
+        #[arg(long)]
+        csv: PathBuf,
+    },
+
+    /// Transfer the raffle proceeds to the creator
+    ClaimProceeds {
+        #[arg(long)]
+        raffle: String,
+    },
+
+    /// Close a settled raffle
+    CloseRaffle {
+        #[arg(long)]
+        raffle: String,
+    },
+}
+
+
+fn main() -> Result<()> {
+
+    let cli = Cli::parse();
+
+    // Needs neither a keypair nor an RPC connection
+    if let Command::ValidateRaffle { config, raffle } = cli.command {
+        return commands::validate_raffle(config.as_deref(), raffle);
+    }
+
+    let keypair_path = keypair_path(&cli.keypair);
+    let payer = read_keypair_file(&keypair_path)
+        .map_err(|err| anyhow!("reading keypair {}: {}", keypair_path.display(), err))?;
+    let client = Client::new(cli.url, payer, cli.dry_run);
+
+    match cli.command {
+        Command::InitAdmin { admin } => commands::init_admin(&client, &admin),
+        Command::SetAdmin { admin } => commands::set_admin(&client, &admin),
+        Command::AcceptAdmin => commands::accept_admin(&client),
+        Command::CreateRaffle { config, raffle } => commands::create_raffle(&client, config.as_deref(), raffle),
+        Command::ValidateRaffle { .. } => unreachable!(),
+        Command::ListRaffles => commands::list_raffles(&client),
+        Command::ListEntrants { raffle } => commands::list_entrants(&client, &raffle),
+        Command::SetRewards { raffle, csv } => commands::set_rewards(&client, &raffle, &csv),
+        Command::ClaimProceeds { raffle } => commands::claim_proceeds(&client, &raffle),
+        Command::CloseRaffle { raffle } => commands::close_raffle(&client, &raffle),
+    }
+}
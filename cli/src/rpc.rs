@@ -0,0 +1,100 @@
+use anchor_lang::{AccountDeserialize, Discriminator};
+use anyhow::{bail, Context, Result};
+use shitcoin_raffle_client::errors::decode_instruction_error;
+use shitcoin_raffle_client::rpc;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::{Transaction, TransactionError};
+
+
+/*
+ * RPC connection and signer shared by all commands
+ */
+
+pub struct Client {
+    pub rpc: RpcClient,
+    pub payer: Keypair,
+    pub dry_run: bool,
+}
+
+
+impl Client {
+
+    pub fn new(url: String, payer: Keypair, dry_run: bool) -> Self {
+        Client {
+            rpc: RpcClient::new_with_commitment(url, CommitmentConfig::confirmed()),
+            payer,
+            dry_run,
+        }
+    }
+
+    pub fn pubkey(&self) -> Pubkey {
+        self.payer.pubkey()
+    }
+
+    // Sends the instructions in a single transaction, or only simulates it on a dry run
+    pub fn send(&self, instructions: &[Instruction]) -> Result<()> {
+        let blockhash = self.rpc.get_latest_blockhash().context("fetching blockhash")?;
+        let tx = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer.pubkey()),
+            &[&self.payer],
+            blockhash,
+        );
+
+        if self.dry_run {
+            let result = self.rpc.simulate_transaction(&tx).context("simulating transaction")?.value;
+            for log in result.logs.unwrap_or_default() {
+                println!("  {}", log);
+            }
+
+            if let Some(err) = result.err {
+                bail!("simulation failed: {}", describe_error(&err));
+            }
+
+            println!("simulation succeeded, {} compute units", result.units_consumed.unwrap_or_default());
+            return Ok(());
+        }
+
+        match self.rpc.send_and_confirm_transaction(&tx) {
+            Ok(signature) => {
+                println!("{}", signature);
+                Ok(())
+            }
+            Err(err) => match err.get_transaction_error() {
+                Some(tx_err) => bail!("transaction failed: {}", describe_error(&tx_err)),
+                None => Err(err).context("sending transaction"),
+            },
+        }
+    }
+
+    pub fn account<T: AccountDeserialize>(&self, address: &Pubkey) -> Result<T> {
+        let data = self.rpc.get_account_data(address)
+            .with_context(|| format!("fetching account {}", address))?;
+
+        T::try_deserialize(&mut &data[..]).with_context(|| format!("decoding account {}", address))
+    }
+
+    // All program accounts of the given type, optionally filtered by a pubkey field at `offset`
+    pub fn program_accounts<T: AccountDeserialize + Discriminator>(
+        &self,
+        field: Option<(usize, &Pubkey)>,
+    ) -> Result<Vec<(Pubkey, T)>> {
+        rpc::program_accounts(&self.rpc, field).context("fetching program accounts")
+    }
+}
+
+
+// Names the program error behind a failed transaction when possible
+fn describe_error(err: &TransactionError) -> String {
+    if let TransactionError::InstructionError(index, ix_err) = err {
+        if let Some(raffle_err) = decode_instruction_error(ix_err) {
+            return format!("instruction {}: {:?} ({})", index, raffle_err, raffle_err);
+        }
+    }
+
+    err.to_string()
+}
@@ -0,0 +1,35 @@
+use anchor_lang::{AccountDeserialize, Result};
+
+use crate::{AdminSettings, Entrant, Raffle, Referrer};
+
+
+/*
+ * Deserialize raw account data, the discriminator is checked before decoding
+ */
+
+pub fn decode_admin_settings(data: &[u8]) -> Result<AdminSettings> {
+    AdminSettings::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_raffle(data: &[u8]) -> Result<Raffle> {
+    Raffle::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_entrant(data: &[u8]) -> Result<Entrant> {
+    Entrant::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_referrer(data: &[u8]) -> Result<Referrer> {
+    Referrer::try_deserialize(&mut &data[..])
+}
+
+
+/*
+ * Offsets of fields used to filter program accounts, the discriminator takes the first 8 bytes
+ */
+
+// Entrant.raffle, after the discriminator, version, bump and user
+pub const ENTRANT_RAFFLE_OFFSET: usize = 8 + 1 + 1 + 32;
+
+// Referrer.raffle, after the discriminator, version, bump and user
+pub const REFERRER_RAFFLE_OFFSET: usize = 8 + 1 + 1 + 32;
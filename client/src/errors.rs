@@ -0,0 +1,97 @@
+use anchor_lang::error::ERROR_CODE_OFFSET;
+use anchor_lang::solana_program::instruction::InstructionError;
+
+use crate::RaffleError;
+
+
+/*
+ * Every program error, in the order of their codes
+ */
+
+pub const RAFFLE_ERRORS: [RaffleError; 45] = [
+    RaffleError::InvalidCalculation,
+    RaffleError::StartAfterEndTimestamp,
+    RaffleError::EndTimestampAlreadyPassed,
+    RaffleError::FeeGreaterThanPrice,
+    RaffleError::RewardsNumGreaterThanTickets,
+    RaffleError::LimitLessThanOne,
+    RaffleError::RaffleTicketsUnavailable,
+    RaffleError::EntrantTicketLimitReached,
+    RaffleError::RaffleNotStarted,
+    RaffleError::RaffleEnded,
+    RaffleError::RewardsNumGreaterThanTicketsBought,
+    RaffleError::RewardsAmountGreaterThanTotal,
+    RaffleError::RaffleSoldOut,
+    RaffleError::RaffleStillActive,
+    RaffleError::RaffleRewardsNotSet,
+    RaffleError::NotEnoughTicketsLeft,
+    RaffleError::RaffleAdminAlreadyClaimed,
+    RaffleError::RaffleAdminNotClaimed,
+    RaffleError::RaffleRewardsNotClaimed,
+    RaffleError::EntrantNotAwarded,
+    RaffleError::EntrantAlreadyAwarded,
+    RaffleError::ReferralShareTooLarge,
+    RaffleError::SelfReferral,
+    RaffleError::RaffleReferralsNotClaimed,
+    RaffleError::AdminNotProposed,
+    RaffleError::ProgramPaused,
+    RaffleError::RafflePaused,
+    RaffleError::RaffleAlreadyStarted,
+    RaffleError::ExtensionTooLarge,
+    RaffleError::ShorteningNotAllowed,
+    RaffleError::EntrantHasNoTickets,
+    RaffleError::InvalidExtension,
+    RaffleError::InvalidRaffleStatus,
+    RaffleError::InvalidStatusTransition,
+    RaffleError::RaffleHasTicketsSold,
+    RaffleError::InvalidClaimPeriod,
+    RaffleError::ClaimDeadlineNotPassed,
+    RaffleError::RaffleAlreadySwept,
+    RaffleError::RaffleRefundsNotClaimed,
+    RaffleError::InvalidSettlementAccounts,
+    RaffleError::EntrantHasTickets,
+    RaffleError::InvalidMigrationAccount,
+    RaffleError::AccountAlreadyMigrated,
+    RaffleError::VaultInsolvent,
+    RaffleError::InvalidMaxExtension,
+];
+
+
+/*
+ * Decode a custom program error code into a RaffleError
+ */
+
+pub fn decode_error_code(code: u32) -> Option<RaffleError> {
+    let index = code.checked_sub(ERROR_CODE_OFFSET)? as usize;
+    RAFFLE_ERRORS.get(index).copied()
+}
+
+pub fn decode_instruction_error(error: &InstructionError) -> Option<RaffleError> {
+    match error {
+        InstructionError::Custom(code) => decode_error_code(*code),
+        _ => None,
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_error_decodes_from_its_code() {
+        for (index, error) in RAFFLE_ERRORS.iter().enumerate() {
+            let code = u32::from(*error);
+            assert_eq!(code, ERROR_CODE_OFFSET + index as u32);
+            assert_eq!(decode_error_code(code).map(u32::from), Some(code));
+        }
+    }
+
+    #[test]
+    fn unknown_codes_do_not_decode() {
+        assert!(decode_error_code(0).is_none());
+        assert!(decode_error_code(ERROR_CODE_OFFSET - 1).is_none());
+        assert!(decode_error_code(ERROR_CODE_OFFSET + RAFFLE_ERRORS.len() as u32).is_none());
+        assert!(decode_instruction_error(&InstructionError::InvalidArgument).is_none());
+    }
+}
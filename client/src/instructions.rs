@@ -0,0 +1,596 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_spl::{associated_token, token_2022};
+use shitcoin_raffle::{accounts, instruction};
+
+use crate::{pda, Role, ID};
+
+pub use shitcoin_raffle::validation::RaffleParams;
+
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+
+/*
+ * Raffle program config instructions, signed by the upgrade authority unless noted otherwise
+ */
+
+pub fn init_admin(authority: &Pubkey, admin: &Pubkey) -> Instruction {
+    build(
+        accounts::InitAdmin {
+            admin_settings: pda::admin_settings().0,
+            program_data: pda::program_data().0,
+            authority: *authority,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::InitAdmin { admin: *admin },
+    )
+}
+
+pub fn propose_admin(authority: &Pubkey, admin: &Pubkey) -> Instruction {
+    build(
+        accounts::ProposeAdmin {
+            admin_settings: pda::admin_settings().0,
+            program_data: pda::program_data().0,
+            authority: *authority,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::ProposeAdmin { admin: *admin },
+    )
+}
+
+// Deprecated, hands the admin role over without the new admin's signature
+pub fn set_admin(authority: &Pubkey, admin: &Pubkey) -> Instruction {
+    build(
+        accounts::SetAdmin {
+            admin_settings: pda::admin_settings().0,
+            program_data: pda::program_data().0,
+            authority: *authority,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::SetAdmin { admin: *admin },
+    )
+}
+
+// Signed by the proposed admin
+pub fn accept_admin(admin: &Pubkey) -> Instruction {
+    build(
+        accounts::AcceptAdmin {
+            admin_settings: pda::admin_settings().0,
+            admin: *admin,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::AcceptAdmin {},
+    )
+}
+
+pub fn cancel_admin(authority: &Pubkey) -> Instruction {
+    build(
+        accounts::CancelAdmin {
+            admin_settings: pda::admin_settings().0,
+            program_data: pda::program_data().0,
+            authority: *authority,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::CancelAdmin {},
+    )
+}
+
+pub fn set_creation_fee(authority: &Pubkey, creation_fee: u64) -> Instruction {
+    build(
+        accounts::SetCreationFee {
+            admin_settings: pda::admin_settings().0,
+            program_data: pda::program_data().0,
+            authority: *authority,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::SetCreationFee { creation_fee },
+    )
+}
+
+pub fn set_max_extension(authority: &Pubkey, max_extension: i64) -> Instruction {
+    build(
+        accounts::SetMaxExtension {
+            admin_settings: pda::admin_settings().0,
+            program_data: pda::program_data().0,
+            authority: *authority,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::SetMaxExtension { max_extension },
+    )
+}
+
+pub fn grant_role(authority: &Pubkey, role: Role, key: &Pubkey) -> Instruction {
+    build(
+        accounts::GrantRole {
+            admin_settings: pda::admin_settings().0,
+            program_data: pda::program_data().0,
+            authority: *authority,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::GrantRole { role, key: *key },
+    )
+}
+
+pub fn revoke_role(authority: &Pubkey, role: Role) -> Instruction {
+    build(
+        accounts::RevokeRole {
+            admin_settings: pda::admin_settings().0,
+            program_data: pda::program_data().0,
+            authority: *authority,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::RevokeRole { role },
+    )
+}
+
+// Signed by the admin or the pauser
+pub fn set_paused(authority: &Pubkey, paused: bool) -> Instruction {
+    build(
+        accounts::SetPaused {
+            admin_settings: pda::admin_settings().0,
+            authority: *authority,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::SetPaused { paused },
+    )
+}
+
+
+/*
+ * Entrant instructions
+ */
+
+pub fn init_entrant(raffle: &Pubkey, user: &Pubkey) -> Instruction {
+    build(
+        accounts::InitEntrant {
+            admin_settings: pda::admin_settings().0,
+            raffle: *raffle,
+            entrant: pda::entrant(raffle, user).0,
+            user: *user,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::InitEntrant {},
+    )
+}
+
+pub fn cancel_entrant(raffle: &Pubkey, user: &Pubkey) -> Instruction {
+    build(
+        accounts::CancelEntrant {
+            raffle: *raffle,
+            entrant: pda::entrant(raffle, user).0,
+            user: *user,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::CancelEntrant {},
+    )
+}
+
+pub fn close_entrant(raffle: &Pubkey, currency: &Pubkey, reward: &Pubkey, user: &Pubkey) -> Instruction {
+    build(
+        accounts::CloseEntrant {
+            admin_settings: pda::admin_settings().0,
+            raffle: *raffle,
+            entrant: pda::entrant(raffle, user).0,
+            proceeds: pda::proceeds(raffle).0,
+            user_proceeds: pda::token_account(user, currency),
+            currency: *currency,
+            user_reward: pda::token_account(user, reward),
+            reward: *reward,
+            user: *user,
+            token_program: token_2022::ID,
+            associated_token_program: associated_token::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::CloseEntrant {},
+    )
+}
+
+// Settles the entrants of the given users, signed and paid for by the crank
+pub fn settle_entrants(
+    raffle: &Pubkey,
+    currency: &Pubkey,
+    reward: &Pubkey,
+    payer: &Pubkey,
+    users: &[Pubkey],
+) -> Instruction {
+    let mut ix = build(
+        accounts::SettleEntrants {
+            admin_settings: pda::admin_settings().0,
+            raffle: *raffle,
+            proceeds: pda::proceeds(raffle).0,
+            currency: *currency,
+            reward: *reward,
+            payer: *payer,
+            token_program: token_2022::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::SettleEntrants {},
+    );
+
+    for user in users {
+        ix.accounts.push(AccountMeta::new(pda::entrant(raffle, user).0, false));
+        ix.accounts.push(AccountMeta::new(*user, false));
+        ix.accounts.push(AccountMeta::new(pda::token_account(user, currency), false));
+        ix.accounts.push(AccountMeta::new(pda::token_account(user, reward), false));
+    }
+
+    ix
+}
+
+pub fn set_shorten_opt_in(raffle: &Pubkey, user: &Pubkey, opt_in: bool) -> Instruction {
+    build(
+        accounts::SetShortenOptIn {
+            raffle: *raffle,
+            entrant: pda::entrant(raffle, user).0,
+            user: *user,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::SetShortenOptIn { opt_in },
+    )
+}
+
+
+/*
+ * Referrer instructions
+ */
+
+pub fn init_referrer(raffle: &Pubkey, user: &Pubkey) -> Instruction {
+    build(
+        accounts::InitReferrer {
+            raffle: *raffle,
+            referrer: pda::referrer(raffle, user).0,
+            user: *user,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::InitReferrer {},
+    )
+}
+
+pub fn close_referrer(raffle: &Pubkey, currency: &Pubkey, user: &Pubkey) -> Instruction {
+    build(
+        accounts::CloseReferrer {
+            raffle: *raffle,
+            referrer: pda::referrer(raffle, user).0,
+            proceeds: pda::proceeds(raffle).0,
+            user_proceeds: pda::token_account(user, currency),
+            currency: *currency,
+            user: *user,
+            token_program: token_2022::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::CloseReferrer {},
+    )
+}
+
+
+/*
+ * Raffle instructions
+ */
+
+// The treasury is the AdminSettings treasurer, or the admin if no treasurer is set
+pub fn init_raffle(
+    seed: &Pubkey,
+    currency: &Pubkey,
+    reward: &Pubkey,
+    treasury: &Pubkey,
+    creator: &Pubkey,
+    params: &RaffleParams,
+) -> Instruction {
+    let raffle = pda::raffle(seed).0;
+
+    build(
+        accounts::InitRaffle {
+            admin_settings: pda::admin_settings().0,
+            raffle,
+            proceeds: pda::proceeds(&raffle).0,
+            currency: *currency,
+            reward: *reward,
+            treasury: *treasury,
+            creator: *creator,
+            token_program: token_2022::ID,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::InitRaffle {
+            seed: *seed,
+            price: params.price,
+            rewards_num: params.rewards_num,
+            rewards_amount: params.rewards_amount,
+            start_timestamp: params.start_timestamp,
+            end_timestamp: params.end_timestamp,
+            fee: params.fee,
+            tickets: params.tickets,
+            limit: params.limit,
+            referral_share: params.referral_share,
+            extension_window: params.extension_window,
+            extension_duration: params.extension_duration,
+            max_auto_extension: params.max_auto_extension,
+            claim_period: params.claim_period,
+        },
+    )
+}
+
+pub fn update_raffle(raffle: &Pubkey, authority: &Pubkey, params: &RaffleParams) -> Instruction {
+    build(
+        accounts::UpdateRaffle {
+            admin_settings: pda::admin_settings().0,
+            raffle: *raffle,
+            authority: *authority,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::UpdateRaffle {
+            price: params.price,
+            rewards_num: params.rewards_num,
+            rewards_amount: params.rewards_amount,
+            start_timestamp: params.start_timestamp,
+            end_timestamp: params.end_timestamp,
+            fee: params.fee,
+            tickets: params.tickets,
+            limit: params.limit,
+            referral_share: params.referral_share,
+            extension_window: params.extension_window,
+            extension_duration: params.extension_duration,
+            max_auto_extension: params.max_auto_extension,
+            claim_period: params.claim_period,
+        },
+    )
+}
+
+// The referrer is the wallet that referred the user, not its referrer account
+pub fn buy_tickets(
+    raffle: &Pubkey,
+    currency: &Pubkey,
+    user: &Pubkey,
+    referrer: Option<&Pubkey>,
+    amount: u64,
+) -> Instruction {
+    build(
+        accounts::BuyTickets {
+            admin_settings: pda::admin_settings().0,
+            raffle: *raffle,
+            entrant: pda::entrant(raffle, user).0,
+            referrer: referrer.map(|referrer| pda::referrer(raffle, referrer).0),
+            proceeds: pda::proceeds(raffle).0,
+            user_proceeds: pda::token_account(user, currency),
+            currency: *currency,
+            user: *user,
+            token_program: token_2022::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::BuyTickets { amount },
+    )
+}
+
+pub fn set_reward(raffle: &Pubkey, authority: &Pubkey, user: &Pubkey, amount: u64) -> Instruction {
+    build(
+        accounts::SetRewards {
+            admin_settings: pda::admin_settings().0,
+            raffle: *raffle,
+            entrant: pda::entrant(raffle, user).0,
+            authority: *authority,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::SetReward { user: *user, amount },
+    )
+}
+
+pub fn claim_proceeds(raffle: &Pubkey, currency: &Pubkey, creator: &Pubkey, authority: &Pubkey) -> Instruction {
+    build(
+        accounts::ClaimProceeds {
+            admin_settings: pda::admin_settings().0,
+            raffle: *raffle,
+            proceeds: pda::proceeds(raffle).0,
+            creator_proceeds: pda::token_account(creator, currency),
+            currency: *currency,
+            creator: *creator,
+            authority: *authority,
+            token_program: token_2022::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::ClaimProceeds {},
+    )
+}
+
+pub fn close_raffle(raffle: &Pubkey, currency: &Pubkey, creator: &Pubkey, authority: &Pubkey) -> Instruction {
+    build(
+        accounts::CloseRaffle {
+            admin_settings: pda::admin_settings().0,
+            raffle: *raffle,
+            proceeds: pda::proceeds(raffle).0,
+            currency: *currency,
+            creator: *creator,
+            authority: *authority,
+            token_program: token_2022::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::CloseRaffle {},
+    )
+}
+
+pub fn sweep_unclaimed(raffle: &Pubkey, currency: &Pubkey, treasury: &Pubkey, authority: &Pubkey) -> Instruction {
+    build(
+        accounts::SweepUnclaimed {
+            admin_settings: pda::admin_settings().0,
+            raffle: *raffle,
+            proceeds: pda::proceeds(raffle).0,
+            treasury_proceeds: pda::token_account(treasury, currency),
+            currency: *currency,
+            treasury: *treasury,
+            authority: *authority,
+            token_program: token_2022::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::SweepUnclaimed {},
+    )
+}
+
+// Sweeps a token account owned by the raffle, the proceeds account is only swept above what the
+// raffle owes
+pub fn sweep_excess(raffle: &Pubkey, vault: &Pubkey, mint: &Pubkey, treasury: &Pubkey, authority: &Pubkey) -> Instruction {
+    build(
+        accounts::SweepExcess {
+            admin_settings: pda::admin_settings().0,
+            raffle: *raffle,
+            vault: *vault,
+            treasury_vault: pda::token_account(treasury, mint),
+            mint: *mint,
+            treasury: *treasury,
+            authority: *authority,
+            token_program: token_2022::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::SweepExcess {},
+    )
+}
+
+pub fn verify_solvency(raffle: &Pubkey) -> Instruction {
+    build(
+        accounts::VerifySolvency {
+            raffle: *raffle,
+            proceeds: pda::proceeds(raffle).0,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::VerifySolvency {},
+    )
+}
+
+pub fn cancel_raffle(raffle: &Pubkey, authority: &Pubkey) -> Instruction {
+    build(
+        accounts::CancelRaffle {
+            admin_settings: pda::admin_settings().0,
+            raffle: *raffle,
+            authority: *authority,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::CancelRaffle {},
+    )
+}
+
+pub fn crank_status(raffle: &Pubkey) -> Instruction {
+    build(
+        accounts::CrankStatus {
+            raffle: *raffle,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::CrankStatus {},
+    )
+}
+
+pub fn set_end_timestamp(raffle: &Pubkey, authority: &Pubkey, end_timestamp: i64) -> Instruction {
+    build(
+        accounts::SetEndTimestamp {
+            admin_settings: pda::admin_settings().0,
+            raffle: *raffle,
+            authority: *authority,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::SetEndTimestamp { end_timestamp },
+    )
+}
+
+pub fn set_raffle_paused(raffle: &Pubkey, authority: &Pubkey, paused: bool, extend: bool) -> Instruction {
+    build(
+        accounts::SetRafflePaused {
+            admin_settings: pda::admin_settings().0,
+            raffle: *raffle,
+            authority: *authority,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::SetRafflePaused { paused, extend },
+    )
+}
+
+
+/*
+ * Migration instructions
+ */
+
+pub fn migrate_admin_settings(authority: &Pubkey) -> Instruction {
+    build(
+        accounts::MigrateAdminSettings {
+            admin_settings: pda::admin_settings().0,
+            program_data: pda::program_data().0,
+            authority: *authority,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::MigrateAdminSettings {},
+    )
+}
+
+pub fn migrate_raffle(raffle: &Pubkey, authority: &Pubkey) -> Instruction {
+    build(
+        accounts::MigrateRaffle {
+            admin_settings: pda::admin_settings().0,
+            raffle: *raffle,
+            proceeds: pda::proceeds(raffle).0,
+            authority: *authority,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::MigrateRaffle {},
+    )
+}
+
+pub fn migrate_entrant(entrant: &Pubkey, authority: &Pubkey) -> Instruction {
+    build(
+        accounts::MigrateEntrant {
+            admin_settings: pda::admin_settings().0,
+            entrant: *entrant,
+            authority: *authority,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        },
+        instruction::MigrateEntrant {},
+    )
+}
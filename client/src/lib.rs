@@ -0,0 +1,10 @@
+pub mod accounts;
+pub mod errors;
+pub mod instructions;
+pub mod pda;
+#[cfg(feature = "rpc")]
+pub mod rpc;
+
+pub use shitcoin_raffle::ID;
+pub use shitcoin_raffle::errors::RaffleError;
+pub use shitcoin_raffle::states::{AdminSettings, Entrant, Raffle, RaffleStatus, Referrer, Role};
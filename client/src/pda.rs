@@ -0,0 +1,55 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::token_2022;
+
+use crate::ID;
+
+
+/*
+ * Program derived addresses, mirroring the seeds used by the program
+ */
+
+// AdminSettings account
+pub fn admin_settings() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"admin".as_ref()], &ID)
+}
+
+// Raffle account created with the given seed
+pub fn raffle(seed: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"raffle".as_ref(), seed.as_ref()], &ID)
+}
+
+// Proceeds token account of a raffle
+pub fn proceeds(raffle: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"proceeds".as_ref(), raffle.as_ref()], &ID)
+}
+
+// Entrant account of a user in a raffle
+pub fn entrant(raffle: &Pubkey, user: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"entrant".as_ref(), raffle.as_ref(), user.as_ref()], &ID)
+}
+
+// Referrer account of a user in a raffle
+pub fn referrer(raffle: &Pubkey, user: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"referrer".as_ref(), raffle.as_ref(), user.as_ref()], &ID)
+}
+
+// Authority used by the program to emit events through a self CPI
+pub fn event_authority() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"__event_authority".as_ref()], &ID)
+}
+
+// Program data account holding the program's upgrade authority
+pub fn program_data() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ID.as_ref()], &bpf_loader_upgradeable::ID)
+}
+
+
+/*
+ * Token-2022 associated token account of a wallet
+ */
+
+pub fn token_account(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(owner, mint, &token_2022::ID)
+}
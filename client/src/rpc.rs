@@ -0,0 +1,54 @@
+use std::path::PathBuf;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AccountDeserialize, Discriminator};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::client_error::Result;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+
+use crate::ID;
+
+
+/*
+ * RPC helpers shared by the command line tools
+ */
+
+// Program accounts of the given type, optionally filtered by a pubkey field at `offset`.
+// Accounts that fail to decode, e.g. ones still in a legacy layout, are skipped
+pub fn program_accounts<T: AccountDeserialize + Discriminator>(
+    rpc: &RpcClient,
+    field: Option<(usize, &Pubkey)>,
+) -> Result<Vec<(Pubkey, T)>> {
+    let mut filters = vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, &T::discriminator()))];
+    if let Some((offset, key)) = field {
+        filters.push(RpcFilterType::Memcmp(Memcmp::new_base58_encoded(offset, key.as_ref())));
+    }
+
+    let config = RpcProgramAccountsConfig {
+        filters: Some(filters),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+
+    let accounts = rpc.get_program_accounts_with_config(&ID, config)?;
+
+    Ok(accounts
+        .into_iter()
+        .filter_map(|(address, account)| {
+            T::try_deserialize(&mut &account.data[..]).ok().map(|decoded| (address, decoded))
+        })
+        .collect())
+}
+
+// Expands a leading `~/` of a keypair path to the home directory
+pub fn keypair_path(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
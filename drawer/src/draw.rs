@@ -0,0 +1,166 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::hash::hashv;
+
+
+/*
+ * Seeded draw of the winning tickets
+ *
+ * The draw is reproducible by anyone who knows the seed and the entrants:
+ *
+ * 1. Entrants with tickets are ordered by user pubkey.
+ * 2. The draw seed is SHA-256(seed || raffle address).
+ * 3. Random numbers are the first 8 bytes, little endian, of SHA-256(draw seed || counter), the
+ *    counter being a little endian u64 that starts at 0 and is incremented on every hash.
+ *    Numbers at or above the largest multiple of the number of remaining tickets are discarded,
+ *    so every ticket is equally likely.
+ * 4. Each of the min(rewards, tickets) draws takes the random number modulo the number of
+ *    remaining tickets, counts that far through the remaining tickets in entrant order and awards
+ *    one reward to the entrant holding the ticket. The ticket is then removed from the draw.
+ *
+ * An entrant's chance of winning is therefore weighted by its tickets, and no entrant can win
+ * more rewards than it has tickets.
+ */
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Candidate {
+
+    // User of the entrant
+    pub user: Pubkey,
+
+    // Number of entry tickets
+    pub tickets: u64,
+}
+
+struct Stream {
+    seed: [u8; 32],
+    counter: u64,
+}
+
+
+impl Stream {
+
+    fn new(seed: &[u8], raffle: &Pubkey) -> Self {
+        Stream {
+            seed: hashv(&[seed, raffle.as_ref()]).to_bytes(),
+            counter: 0,
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let hash = hashv(&[&self.seed, &self.counter.to_le_bytes()]).to_bytes();
+        self.counter += 1;
+
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&hash[..8]);
+        u64::from_le_bytes(bytes)
+    }
+
+    // Uniform number in [0, bound)
+    fn below(&mut self, bound: u64) -> u64 {
+        let zone = u64::MAX - (u64::MAX % bound);
+        loop {
+            let number = self.next_u64();
+            if number < zone {
+                return number % bound;
+            }
+        }
+    }
+}
+
+
+// Returns the winners ordered by user, with the number of rewards each of them won
+pub fn draw(seed: &[u8], raffle: &Pubkey, candidates: &[Candidate], rewards: u64) -> Vec<(Pubkey, u64)> {
+    let mut remaining: Vec<Candidate> = candidates.iter().filter(|c| c.tickets > 0).cloned().collect();
+    remaining.sort_by_key(|c| c.user);
+
+    let mut wins = vec![0u64; remaining.len()];
+    let mut remaining_tickets: u64 = remaining.iter().map(|c| c.tickets).sum();
+    let mut stream = Stream::new(seed, raffle);
+
+    for _ in 0..rewards.min(remaining_tickets) {
+        let mut ticket = stream.below(remaining_tickets);
+
+        for (index, candidate) in remaining.iter_mut().enumerate() {
+            if ticket < candidate.tickets {
+                candidate.tickets -= 1;
+                wins[index] += 1;
+                break;
+            }
+
+            ticket -= candidate.tickets;
+        }
+
+        remaining_tickets -= 1;
+    }
+
+    remaining
+        .iter()
+        .zip(wins)
+        .filter(|(_, won)| *won > 0)
+        .map(|(candidate, won)| (candidate.user, won))
+        .collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidates() -> Vec<Candidate> {
+        (1..=10)
+            .map(|tickets| Candidate { user: Pubkey::new_unique(), tickets })
+            .collect()
+    }
+
+    fn total(winners: &[(Pubkey, u64)]) -> u64 {
+        winners.iter().map(|(_, won)| won).sum()
+    }
+
+    #[test]
+    fn draw_is_reproducible() {
+        let raffle = Pubkey::new_unique();
+        let candidates = candidates();
+
+        let mut shuffled = candidates.clone();
+        shuffled.reverse();
+
+        assert_eq!(draw(b"seed", &raffle, &candidates, 7), draw(b"seed", &raffle, &shuffled, 7));
+    }
+
+    #[test]
+    fn draw_depends_on_the_seed() {
+        let raffle = Pubkey::new_unique();
+        let candidates = candidates();
+
+        let first = draw(b"seed-0", &raffle, &candidates, 7);
+        assert!((1..10).any(|i| draw(format!("seed-{}", i).as_bytes(), &raffle, &candidates, 7) != first));
+    }
+
+    #[test]
+    fn draw_awards_every_reward_within_the_tickets() {
+        let raffle = Pubkey::new_unique();
+        let candidates = candidates();
+
+        for rewards in [0, 1, 7, 55, 100] {
+            let winners = draw(b"seed", &raffle, &candidates, rewards);
+            assert_eq!(total(&winners), rewards.min(55));
+
+            for (user, won) in winners {
+                let candidate = candidates.iter().find(|c| c.user == user).unwrap();
+                assert!(won <= candidate.tickets);
+            }
+        }
+    }
+
+    #[test]
+    fn draw_skips_entrants_without_tickets() {
+        let raffle = Pubkey::new_unique();
+        let candidates = vec![
+            Candidate { user: Pubkey::new_unique(), tickets: 0 },
+            Candidate { user: Pubkey::new_unique(), tickets: 2 },
+        ];
+
+        assert_eq!(draw(b"seed", &raffle, &candidates, 5), vec![(candidates[1].user, 2)]);
+        assert!(draw(b"seed", &raffle, &[], 5).is_empty());
+    }
+}
@@ -0,0 +1,121 @@
+pub mod draw;
+
+use anchor_lang::AccountDeserialize;
+use anyhow::{anyhow, Context, Result};
+use shitcoin_raffle_client::accounts::ENTRANT_RAFFLE_OFFSET;
+use shitcoin_raffle_client::{instructions, rpc, Entrant, Raffle, RaffleStatus};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::Transaction;
+
+use draw::{draw, Candidate};
+
+// Number of set_reward instructions packed into one transaction
+const REWARDS_PER_TRANSACTION: usize = 5;
+
+// Attempts at awarding a raffle within one pass before moving on
+const MAX_ATTEMPTS: usize = 5;
+
+
+/*
+ * Drawing service, every pass awards the rewards of the raffles that ended
+ */
+
+pub struct Drawer {
+    pub rpc: RpcClient,
+    pub payer: Keypair,
+    pub seed: Vec<u8>,
+}
+
+
+impl Drawer {
+
+    // Awards every raffle that ended and still has rewards to set
+    pub fn pass(&self, only: Option<&Pubkey>) -> Result<()> {
+        let now = self.rpc.get_block_time(self.rpc.get_slot()?).context("fetching cluster time")?;
+
+        for (address, raffle) in rpc::program_accounts::<Raffle>(&self.rpc, None)? {
+            if only.map_or(false, |only| *only != address) || !is_drawable(&raffle, now) {
+                continue;
+            }
+
+            if let Err(err) = self.award(&address) {
+                eprintln!("{}: {:#}", address, err);
+            }
+        }
+
+        Ok(())
+    }
+
+    // Sets the drawn rewards, retrying until rewards_awarded reaches the raffle's target
+    pub fn award(&self, address: &Pubkey) -> Result<()> {
+        for attempt in 0..=MAX_ATTEMPTS {
+            let raffle: Raffle = self.account(address)?;
+            let target = raffle.get_rewards_target();
+            if raffle.rewards_awarded >= target {
+                println!("{}: {} of {} rewards awarded", address, raffle.rewards_awarded, target);
+                return Ok(());
+            }
+
+            if attempt == MAX_ATTEMPTS {
+                break;
+            }
+
+            let entrants = rpc::program_accounts::<Entrant>(&self.rpc, Some((ENTRANT_RAFFLE_OFFSET, address)))?;
+            let candidates: Vec<Candidate> = entrants
+                .iter()
+                .map(|(_, entrant)| Candidate { user: entrant.user, tickets: entrant.tickets })
+                .collect();
+
+            let mut ixs = Vec::new();
+            for (user, won) in draw(&self.seed, address, &candidates, target) {
+                let (_, entrant) = entrants.iter().find(|(_, entrant)| entrant.user == user).unwrap();
+                if entrant.rewards == 0 {
+                    ixs.push(instructions::set_reward(address, &self.payer.pubkey(), &user, won));
+                } else if entrant.rewards != won {
+                    eprintln!("{}: {} was awarded {} rewards, the draw gives {}", address, user, entrant.rewards, won);
+                }
+            }
+
+            for chunk in ixs.chunks(REWARDS_PER_TRANSACTION) {
+                if let Err(err) = self.send(chunk) {
+                    eprintln!("{}: attempt {}: {:#}", address, attempt, err);
+                }
+            }
+        }
+
+        Err(anyhow!("rewards not awarded after {} attempts", MAX_ATTEMPTS))
+    }
+
+    fn send(&self, instructions: &[Instruction]) -> Result<()> {
+        let blockhash = self.rpc.get_latest_blockhash()?;
+        let tx = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer.pubkey()),
+            &[&self.payer],
+            blockhash,
+        );
+
+        let signature = self.rpc.send_and_confirm_transaction(&tx)?;
+        println!("{}", signature);
+        Ok(())
+    }
+
+    fn account<T: AccountDeserialize>(&self, address: &Pubkey) -> Result<T> {
+        let data = self.rpc.get_account_data(address)?;
+        T::try_deserialize(&mut &data[..]).with_context(|| format!("decoding account {}", address))
+    }
+}
+
+
+// Raffle ended at `now` and is waiting for its rewards
+fn is_drawable(raffle: &Raffle, now: i64) -> bool {
+    let mut raffle = raffle.clone();
+    if raffle.advance_at(now).is_err() {
+        return false;
+    }
+
+    raffle.assert_ended().is_ok() && raffle.status == RaffleStatus::Ended
+}
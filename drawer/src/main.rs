@@ -0,0 +1,71 @@
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use clap::Parser;
+use raffle_drawer::Drawer;
+use shitcoin_raffle_client::rpc::keypair_path;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::read_keypair_file;
+
+
+#[derive(Parser)]
+#[command(name = "raffle-drawer", about = "Draw the winners of ended raffles and set their rewards")]
+struct Cli {
+
+    /// RPC endpoint, defaults to a local solana-test-validator
+    #[arg(long, short = 'u', default_value = "http://127.0.0.1:8899")]
+    url: String,
+
+    /// Keypair of the drawer role or the raffle creator
+    #[arg(long, short = 'k', default_value = "~/.config/solana/id.json")]
+    keypair: String,
+
+    /// Seed of the draw, publish it so the draw can be verified
+    #[arg(long)]
+    seed: String,
+
+    /// Only draw this raffle
+    #[arg(long)]
+    raffle: Option<String>,
+
+    /// Seconds between passes
+    #[arg(long, default_value_t = 10)]
+    interval: u64,
+
+    /// Run a single pass and exit
+    #[arg(long)]
+    once: bool,
+}
+
+
+fn main() -> Result<()> {
+
+    let cli = Cli::parse();
+
+    let keypair_path = keypair_path(&cli.keypair);
+    let payer = read_keypair_file(&keypair_path)
+        .map_err(|err| anyhow!("reading keypair {}: {}", keypair_path.display(), err))?;
+    let only = cli.raffle.as_deref().map(Pubkey::from_str).transpose().context("parsing raffle")?;
+
+    let drawer = Drawer {
+        rpc: RpcClient::new_with_commitment(cli.url, CommitmentConfig::confirmed()),
+        payer,
+        seed: cli.seed.into_bytes(),
+    };
+
+    loop {
+        if let Err(err) = drawer.pass(only.as_ref()) {
+            eprintln!("pass failed: {:#}", err);
+        }
+
+        if cli.once {
+            return Ok(());
+        }
+
+        thread::sleep(Duration::from_secs(cli.interval));
+    }
+}
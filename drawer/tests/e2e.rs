@@ -0,0 +1,178 @@
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::AccountDeserialize;
+use anchor_spl::associated_token::spl_associated_token_account;
+use anchor_spl::token_2022::spl_token_2022;
+use raffle_drawer::Drawer;
+use shitcoin_raffle_client::instructions::{self, RaffleParams};
+use shitcoin_raffle_client::{pda, Raffle, ID};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::Transaction;
+use solana_test_validator::{TestValidatorGenesis, UpgradeableProgramInfo};
+
+
+/*
+ * End-to-end run of the drawer against a local validator
+ *
+ * The test deploys the built program, so run `anchor build` first and then
+ * `cargo test -p raffle-drawer -- --ignored`. The payer of the validator is the upgrade authority,
+ * the admin and the creator of the raffle.
+ */
+
+const DECIMALS: u8 = 6;
+const PRICE: u64 = 100;
+const REWARDS_NUM: u64 = 4;
+const REWARDS_AMOUNT: u64 = 1_000;
+const BALANCE: u64 = 1_000_000;
+
+// Seconds between the creation and the end of the raffle, long enough to buy the tickets
+const DURATION: i64 = 20;
+
+const TIMEOUT: Duration = Duration::from_secs(120);
+
+
+fn send(rpc: &RpcClient, instructions: &[Instruction], payer: &Keypair, signers: &[&Keypair]) {
+    let blockhash = rpc.get_latest_blockhash().unwrap();
+    let mut all = vec![payer];
+    all.extend_from_slice(signers);
+
+    let tx = Transaction::new_signed_with_payer(instructions, Some(&payer.pubkey()), &all, blockhash);
+    rpc.send_and_confirm_transaction(&tx).unwrap();
+}
+
+fn cluster_time(rpc: &RpcClient) -> i64 {
+    rpc.get_block_time(rpc.get_slot().unwrap()).unwrap()
+}
+
+fn create_mint(rpc: &RpcClient, payer: &Keypair, authority: &Pubkey) -> Pubkey {
+    let mint = Keypair::new();
+    let space = spl_token_2022::state::Mint::LEN;
+    let ixs = [
+        system_instruction::create_account(
+            &payer.pubkey(),
+            &mint.pubkey(),
+            rpc.get_minimum_balance_for_rent_exemption(space).unwrap(),
+            space as u64,
+            &spl_token_2022::ID,
+        ),
+        spl_token_2022::instruction::initialize_mint2(&spl_token_2022::ID, &mint.pubkey(), authority, None, DECIMALS).unwrap(),
+    ];
+    send(rpc, &ixs, payer, &[&mint]);
+
+    mint.pubkey()
+}
+
+// Funded wallet holding currency and the associated token account of the reward
+fn user(rpc: &RpcClient, payer: &Keypair, currency: &Pubkey, reward: &Pubkey) -> Keypair {
+    let user = Keypair::new();
+    let mut ixs = vec![system_instruction::transfer(&payer.pubkey(), &user.pubkey(), LAMPORTS_PER_SOL)];
+    for mint in [currency, reward] {
+        ixs.push(spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+            &payer.pubkey(),
+            &user.pubkey(),
+            mint,
+            &spl_token_2022::ID,
+        ));
+    }
+    ixs.push(
+        spl_token_2022::instruction::mint_to(
+            &spl_token_2022::ID,
+            currency,
+            &pda::token_account(&user.pubkey(), currency),
+            &payer.pubkey(),
+            &[],
+            BALANCE,
+        )
+        .unwrap(),
+    );
+    send(rpc, &ixs, payer, &[]);
+
+    user
+}
+
+fn raffle(rpc: &RpcClient, address: &Pubkey) -> Raffle {
+    let data = rpc.get_account_data(address).unwrap();
+    Raffle::try_deserialize(&mut &data[..]).unwrap()
+}
+
+
+#[test]
+#[ignore = "needs the program built by `anchor build`"]
+fn drawer_awards_every_reward() {
+    let program_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../target/deploy/shitcoin_raffle.so");
+    assert!(program_path.exists(), "{} is missing, run `anchor build`", program_path.display());
+
+    let authority = Keypair::new();
+    let (validator, payer) = TestValidatorGenesis::default()
+        .add_upgradeable_programs_with_path(&[UpgradeableProgramInfo {
+            program_id: ID,
+            loader: bpf_loader_upgradeable::id(),
+            upgrade_authority: authority.pubkey(),
+            program_path,
+        }])
+        .start();
+    let rpc = validator.get_rpc_client();
+
+    send(&rpc, &[system_instruction::transfer(&payer.pubkey(), &authority.pubkey(), LAMPORTS_PER_SOL)], &payer, &[]);
+    send(&rpc, &[instructions::init_admin(&authority.pubkey(), &payer.pubkey())], &payer, &[&authority]);
+
+    let currency = create_mint(&rpc, &payer, &payer.pubkey());
+    let seed = Pubkey::new_unique();
+    let address = pda::raffle(&seed).0;
+    let reward = create_mint(&rpc, &payer, &address);
+
+    let now = cluster_time(&rpc);
+    let params = RaffleParams {
+        price: PRICE,
+        rewards_num: REWARDS_NUM,
+        rewards_amount: REWARDS_AMOUNT,
+        start_timestamp: now,
+        end_timestamp: now + DURATION,
+        ..RaffleParams::default()
+    };
+    let ix = instructions::init_raffle(&seed, &currency, &reward, &payer.pubkey(), &payer.pubkey(), &params);
+    send(&rpc, &[ix], &payer, &[]);
+
+    // Fewer rewards than tickets, so the draw has to pick among the entrants
+    for tickets in [1, 2, 3] {
+        let user = user(&rpc, &payer, &currency, &reward);
+        let ixs = [
+            instructions::init_entrant(&address, &user.pubkey()),
+            instructions::buy_tickets(&address, &currency, &user.pubkey(), None, tickets),
+        ];
+        send(&rpc, &ixs, &user, &[]);
+    }
+
+    let started = Instant::now();
+    while cluster_time(&rpc) <= now + DURATION {
+        assert!(started.elapsed() < TIMEOUT, "raffle did not end");
+        thread::sleep(Duration::from_secs(1));
+    }
+
+    let drawer = Drawer { rpc, payer, seed: b"e2e".to_vec() };
+    let target = raffle(&drawer.rpc, &address).get_rewards_target();
+    assert_eq!(target, REWARDS_NUM.min(6));
+
+    loop {
+        drawer.pass(Some(&address)).unwrap();
+        if raffle(&drawer.rpc, &address).rewards_awarded == target {
+            break;
+        }
+
+        assert!(started.elapsed() < TIMEOUT, "rewards not awarded");
+        thread::sleep(Duration::from_secs(1));
+    }
+
+    let raffle = raffle(&drawer.rpc, &address);
+    assert_eq!(raffle.tickets_sold, 6);
+    assert_eq!(raffle.rewards_awarded, raffle.rewards_num.min(raffle.tickets_sold));
+}
@@ -0,0 +1,121 @@
+-- Schema of the raffle indexer database
+--
+-- Every row is derived from the events the program emits through event CPI. Pubkeys and
+-- signatures are stored as base58 text, amounts as integers in the mint's base units. The
+-- tables hold enough to rebuild the counters of any Raffle account, see `Database::raffle_counters`.
+
+
+-- Transactions that have been indexed, used to skip duplicates and to resume following
+CREATE TABLE IF NOT EXISTS transactions (
+    signature       TEXT PRIMARY KEY,
+    slot            INTEGER NOT NULL,
+    block_time      INTEGER
+);
+
+-- Raffles, parameters as of the latest RaffleCreated / RaffleUpdated / EndTimestampChanged event
+CREATE TABLE IF NOT EXISTS raffles (
+    address         TEXT PRIMARY KEY,
+    seed            TEXT NOT NULL,
+    creator         TEXT NOT NULL,
+    currency        TEXT NOT NULL,
+    reward          TEXT NOT NULL,
+    price           INTEGER NOT NULL,
+    fee             INTEGER NOT NULL,
+    rewards_num     INTEGER NOT NULL,
+    rewards_amount  INTEGER NOT NULL,
+    start_timestamp INTEGER NOT NULL,
+    end_timestamp   INTEGER NOT NULL,
+    tickets         INTEGER,                -- NULL if the number of tickets is unlimited
+    ticket_limit    INTEGER,                -- NULL if the tickets per user are unlimited
+    referral_share  INTEGER NOT NULL,       -- basis points of the fee paid to referrers
+    extension_window INTEGER NOT NULL,
+    extension_duration INTEGER NOT NULL,
+    max_auto_extension INTEGER NOT NULL,
+    claim_period    INTEGER,                -- NULL if rewards can be claimed forever
+    creation_fee    INTEGER NOT NULL,
+    status          TEXT,                   -- latest StatusChanged, NULL while the raffle is still Pending
+    cancelled       INTEGER NOT NULL DEFAULT 0,
+    created_slot    INTEGER NOT NULL,
+    closed_slot     INTEGER                 -- NULL while the raffle account exists
+);
+
+-- Entrant accounts
+CREATE TABLE IF NOT EXISTS entrants (
+    address         TEXT PRIMARY KEY,
+    raffle          TEXT NOT NULL,
+    user            TEXT NOT NULL,
+    shorten_opt_in  INTEGER NOT NULL DEFAULT 0,
+    created_slot    INTEGER NOT NULL,
+    closed_slot     INTEGER                 -- NULL while the entrant account exists
+);
+
+CREATE INDEX IF NOT EXISTS entrants_raffle ON entrants (raffle);
+
+-- Referrer accounts
+CREATE TABLE IF NOT EXISTS referrers (
+    address         TEXT PRIMARY KEY,
+    raffle          TEXT NOT NULL,
+    user            TEXT NOT NULL,
+    created_slot    INTEGER NOT NULL,
+    closed_slot     INTEGER                 -- NULL while the referrer account exists
+);
+
+-- Ticket purchases, one row per TicketsPurchased event
+CREATE TABLE IF NOT EXISTS purchases (
+    id              INTEGER PRIMARY KEY,
+    signature       TEXT NOT NULL REFERENCES transactions (signature),
+    slot            INTEGER NOT NULL,
+    raffle          TEXT NOT NULL,
+    user            TEXT NOT NULL,
+    amount          INTEGER NOT NULL,       -- number of tickets bought
+    total_price     INTEGER NOT NULL,
+    tickets_sold    INTEGER NOT NULL,       -- raffle's tickets sold after the purchase
+    referrer        TEXT,                   -- referrer account, NULL without a referral
+    referral_amount INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS purchases_raffle ON purchases (raffle);
+
+-- Winning tickets, one row per RewardSet event
+CREATE TABLE IF NOT EXISTS rewards (
+    id              INTEGER PRIMARY KEY,
+    signature       TEXT NOT NULL REFERENCES transactions (signature),
+    slot            INTEGER NOT NULL,
+    raffle          TEXT NOT NULL,
+    user            TEXT NOT NULL,
+    amount          INTEGER NOT NULL,       -- number of winning tickets
+    rewards_awarded INTEGER NOT NULL        -- raffle's awarded rewards after the change
+);
+
+CREATE INDEX IF NOT EXISTS rewards_raffle ON rewards (raffle);
+
+-- Payouts out of the proceeds vault
+--   kind = 'entrant':  EntrantClosed, `amount` is the refund, `reward_amount` the minted rewards
+--   kind = 'refund':   EntrantRefunded while paused, `amount` is the refund of `tickets` losing tickets
+--   kind = 'referral': ReferrerClosed, `amount` is the paid out referral rewards
+--   kind = 'proceeds': ProceedsClaimed, `amount` is the creator's share
+CREATE TABLE IF NOT EXISTS claims (
+    id              INTEGER PRIMARY KEY,
+    signature       TEXT NOT NULL REFERENCES transactions (signature),
+    slot            INTEGER NOT NULL,
+    raffle          TEXT NOT NULL,
+    user            TEXT NOT NULL,          -- entrant user, referrer user or raffle creator
+    kind            TEXT NOT NULL,
+    amount          INTEGER NOT NULL,
+    reward_amount   INTEGER NOT NULL DEFAULT 0,
+    tickets         INTEGER NOT NULL DEFAULT 0,
+    rewards         INTEGER NOT NULL DEFAULT 0,
+    swept           INTEGER NOT NULL DEFAULT 0  -- entrant closed after the sweep, nothing was paid out
+);
+
+CREATE INDEX IF NOT EXISTS claims_raffle ON claims (raffle);
+
+-- Unclaimed funds swept to the treasury after the claim deadline
+CREATE TABLE IF NOT EXISTS sweeps (
+    raffle            TEXT PRIMARY KEY,
+    signature         TEXT NOT NULL REFERENCES transactions (signature),
+    slot              INTEGER NOT NULL,
+    treasury          TEXT NOT NULL,
+    amount            INTEGER NOT NULL,
+    rewards_forfeited INTEGER NOT NULL
+);
@@ -0,0 +1,285 @@
+use std::path::Path;
+use std::str::FromStr;
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use shitcoin_raffle::ID;
+
+use crate::events::RaffleEvent;
+use crate::fixture::RecordedTransaction;
+
+const SCHEMA: &str = include_str!("../schema.sql");
+
+
+/*
+ * Raffle account counters, rebuilt from the indexed events
+ */
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RaffleCounters {
+    pub end_timestamp: i64,
+    pub tickets_sold: u64,
+    pub entrants: u64,
+    pub shorten_opt_ins: u64,
+    pub rewards_awarded: u64,
+    pub rewards_claimed: u64,
+    pub rewards_forfeited: u64,
+    pub tickets_refunded: u64,
+    pub referral_accrued: u64,
+    pub referral_claimed: u64,
+    pub admin_claimed: bool,
+    pub swept: bool,
+}
+
+
+pub struct Database {
+    conn: Connection,
+}
+
+
+impl Database {
+
+    pub fn open(path: &Path) -> Result<Self> {
+        let conn = Connection::open(path).with_context(|| format!("opening {}", path.display()))?;
+        Self::init(conn)
+    }
+
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        conn.execute_batch(SCHEMA).context("creating schema")?;
+        Ok(Database { conn })
+    }
+
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    // Signature of the most recently indexed transaction
+    pub fn last_signature(&self) -> Result<Option<String>> {
+        let signature = self.conn
+            .query_row(
+                "SELECT signature FROM transactions ORDER BY slot DESC, rowid DESC LIMIT 1",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        Ok(signature)
+    }
+
+    // Indexes the program's events of a transaction, returns false if it was already indexed
+    pub fn apply(&mut self, recorded: &RecordedTransaction) -> Result<bool> {
+        let tx = self.conn.transaction()?;
+
+        let inserted = tx.execute(
+            "INSERT OR IGNORE INTO transactions (signature, slot, block_time) VALUES (?1, ?2, ?3)",
+            params![recorded.signature, recorded.slot, recorded.block_time],
+        )?;
+        if inserted == 0 {
+            return Ok(false);
+        }
+
+        let program_id = ID.to_string();
+        for ix in &recorded.inner_instructions {
+            if ix.program_id != program_id {
+                continue;
+            }
+
+            let data = bs58::decode(&ix.data).into_vec()
+                .with_context(|| format!("decoding instruction data of {}", recorded.signature))?;
+
+            if let Some(event) = RaffleEvent::decode(&data) {
+                apply_event(&tx, &recorded.signature, recorded.slot, event)
+                    .with_context(|| format!("indexing {}", recorded.signature))?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(true)
+    }
+
+    pub fn raffle_counters(&self, raffle: &Pubkey) -> Result<RaffleCounters> {
+        let raffle = raffle.to_string();
+        let query = |sql: &str| -> Result<i64> {
+            Ok(self.conn.query_row(sql, params![raffle], |row| row.get(0))?)
+        };
+
+        Ok(RaffleCounters {
+            end_timestamp: query("SELECT end_timestamp FROM raffles WHERE address = ?1")?,
+            tickets_sold: query("SELECT COALESCE(SUM(amount), 0) FROM purchases WHERE raffle = ?1")? as u64,
+            entrants: query("SELECT COUNT(DISTINCT user) FROM purchases WHERE raffle = ?1")? as u64,
+            shorten_opt_ins: query("SELECT COUNT(*) FROM entrants WHERE raffle = ?1 AND shorten_opt_in = 1")? as u64,
+            rewards_awarded: query("SELECT COALESCE(SUM(amount), 0) FROM rewards WHERE raffle = ?1")? as u64,
+            rewards_claimed: query(
+                "SELECT COALESCE(SUM(rewards), 0) FROM claims WHERE raffle = ?1 AND kind = 'entrant' AND swept = 0"
+            )? as u64,
+            rewards_forfeited: query("SELECT COALESCE(SUM(rewards_forfeited), 0) FROM sweeps WHERE raffle = ?1")? as u64,
+            tickets_refunded: query(
+                "SELECT COALESCE(SUM(tickets - rewards), 0) FROM claims WHERE raffle = ?1 AND kind IN ('entrant', 'refund') AND swept = 0"
+            )? as u64,
+            referral_accrued: query("SELECT COALESCE(SUM(referral_amount), 0) FROM purchases WHERE raffle = ?1")? as u64,
+            referral_claimed: query("SELECT COALESCE(SUM(amount), 0) FROM claims WHERE raffle = ?1 AND kind = 'referral'")? as u64,
+            admin_claimed: query("SELECT COUNT(*) FROM claims WHERE raffle = ?1 AND kind = 'proceeds'")? > 0,
+            swept: query("SELECT COUNT(*) FROM sweeps WHERE raffle = ?1")? > 0,
+        })
+    }
+
+    // Raffles known to the indexer
+    pub fn raffles(&self) -> Result<Vec<Pubkey>> {
+        let mut stmt = self.conn.prepare("SELECT address FROM raffles ORDER BY created_slot")?;
+        let addresses = stmt.query_map([], |row| row.get::<_, String>(0))?;
+
+        addresses
+            .map(|address| Ok(Pubkey::from_str(&address?)?))
+            .collect()
+    }
+}
+
+
+fn apply_event(tx: &Transaction, signature: &str, slot: u64, event: RaffleEvent) -> Result<()> {
+    match event {
+        RaffleEvent::RaffleCreated(e) => {
+            tx.execute(
+                "INSERT INTO raffles (address, seed, creator, currency, reward, price, fee, rewards_num, rewards_amount,
+                    start_timestamp, end_timestamp, tickets, ticket_limit, referral_share, extension_window,
+                    extension_duration, max_auto_extension, claim_period, creation_fee, created_slot)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20)",
+                params![
+                    e.raffle.to_string(), e.seed.to_string(), e.creator.to_string(), e.currency.to_string(),
+                    e.reward.to_string(), e.price, e.fee, e.rewards_num, e.rewards_amount, e.start_timestamp,
+                    e.end_timestamp, e.tickets, e.limit, e.referral_share, e.extension_window, e.extension_duration,
+                    e.max_auto_extension, e.claim_period, e.creation_fee, slot,
+                ],
+            )?;
+        }
+        RaffleEvent::RaffleUpdated(e) => {
+            tx.execute(
+                "UPDATE raffles SET price = ?2, fee = ?3, rewards_num = ?4, rewards_amount = ?5, start_timestamp = ?6,
+                    end_timestamp = ?7, tickets = ?8, ticket_limit = ?9, referral_share = ?10, extension_window = ?11,
+                    extension_duration = ?12, max_auto_extension = ?13, claim_period = ?14
+                 WHERE address = ?1",
+                params![
+                    e.raffle.to_string(), e.price, e.fee, e.rewards_num, e.rewards_amount, e.start_timestamp,
+                    e.end_timestamp, e.tickets, e.limit, e.referral_share, e.extension_window, e.extension_duration,
+                    e.max_auto_extension, e.claim_period,
+                ],
+            )?;
+        }
+        RaffleEvent::EndTimestampChanged(e) => {
+            tx.execute(
+                "UPDATE raffles SET end_timestamp = ?2 WHERE address = ?1",
+                params![e.raffle.to_string(), e.new_end_timestamp],
+            )?;
+        }
+        RaffleEvent::StatusChanged(e) => {
+            tx.execute(
+                "UPDATE raffles SET status = ?2 WHERE address = ?1",
+                params![e.raffle.to_string(), format!("{:?}", e.new_status)],
+            )?;
+        }
+        RaffleEvent::TicketsPurchased(e) => {
+            tx.execute(
+                "INSERT INTO purchases (signature, slot, raffle, user, amount, total_price, tickets_sold, referrer, referral_amount)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    signature, slot, e.raffle.to_string(), e.user.to_string(), e.amount, e.total_price,
+                    e.tickets_sold, e.referrer.map(|referrer| referrer.to_string()), e.referral_amount,
+                ],
+            )?;
+        }
+        RaffleEvent::RewardSet(e) => {
+            tx.execute(
+                "INSERT INTO rewards (signature, slot, raffle, user, amount, rewards_awarded)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![signature, slot, e.raffle.to_string(), e.user.to_string(), e.amount, e.rewards_awarded],
+            )?;
+        }
+        RaffleEvent::ProceedsClaimed(e) => {
+            tx.execute(
+                "INSERT INTO claims (signature, slot, raffle, user, kind, amount) VALUES (?1, ?2, ?3, ?4, 'proceeds', ?5)",
+                params![signature, slot, e.raffle.to_string(), e.creator.to_string(), e.amount],
+            )?;
+        }
+        RaffleEvent::UnclaimedSwept(e) => {
+            tx.execute(
+                "INSERT INTO sweeps (raffle, signature, slot, treasury, amount, rewards_forfeited)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    e.raffle.to_string(), signature, slot, e.treasury.to_string(), e.amount, e.rewards_forfeited,
+                ],
+            )?;
+        }
+        RaffleEvent::RaffleCancelled(e) => {
+            tx.execute(
+                "UPDATE raffles SET cancelled = 1 WHERE address = ?1",
+                params![e.raffle.to_string()],
+            )?;
+        }
+        RaffleEvent::RaffleClosed(e) => {
+            tx.execute(
+                "UPDATE raffles SET closed_slot = ?2 WHERE address = ?1",
+                params![e.raffle.to_string(), slot],
+            )?;
+        }
+        RaffleEvent::EntrantCreated(e) => {
+            tx.execute(
+                "INSERT INTO entrants (address, raffle, user, created_slot) VALUES (?1, ?2, ?3, ?4)",
+                params![e.entrant.to_string(), e.raffle.to_string(), e.user.to_string(), slot],
+            )?;
+        }
+        RaffleEvent::EntrantCancelled(e) => {
+            tx.execute(
+                "UPDATE entrants SET closed_slot = ?2 WHERE address = ?1",
+                params![e.entrant.to_string(), slot],
+            )?;
+        }
+        RaffleEvent::ShortenOptInChanged(e) => {
+            tx.execute(
+                "UPDATE entrants SET shorten_opt_in = ?3 WHERE raffle = ?1 AND user = ?2 AND closed_slot IS NULL",
+                params![e.raffle.to_string(), e.user.to_string(), e.opt_in],
+            )?;
+        }
+        RaffleEvent::EntrantRefunded(e) => {
+            tx.execute(
+                "INSERT INTO claims (signature, slot, raffle, user, kind, amount, tickets) VALUES (?1, ?2, ?3, ?4, 'refund', ?5, ?6)",
+                params![signature, slot, e.raffle.to_string(), e.user.to_string(), e.refund_amount, e.tickets],
+            )?;
+        }
+        RaffleEvent::EntrantClosed(e) => {
+            tx.execute(
+                "INSERT INTO claims (signature, slot, raffle, user, kind, amount, reward_amount, tickets, rewards, swept)
+                 VALUES (?1, ?2, ?3, ?4, 'entrant', ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    signature, slot, e.raffle.to_string(), e.user.to_string(), e.refund_amount, e.reward_amount,
+                    e.tickets, e.rewards, e.swept,
+                ],
+            )?;
+            tx.execute(
+                "UPDATE entrants SET closed_slot = ?2 WHERE address = ?1",
+                params![e.entrant.to_string(), slot],
+            )?;
+        }
+        RaffleEvent::ReferrerCreated(e) => {
+            tx.execute(
+                "INSERT INTO referrers (address, raffle, user, created_slot) VALUES (?1, ?2, ?3, ?4)",
+                params![e.referrer.to_string(), e.raffle.to_string(), e.user.to_string(), slot],
+            )?;
+        }
+        RaffleEvent::ReferrerClosed(e) => {
+            tx.execute(
+                "INSERT INTO claims (signature, slot, raffle, user, kind, amount) VALUES (?1, ?2, ?3, ?4, 'referral', ?5)",
+                params![signature, slot, e.raffle.to_string(), e.user.to_string(), e.amount],
+            )?;
+            tx.execute(
+                "UPDATE referrers SET closed_slot = ?2 WHERE address = ?1",
+                params![e.referrer.to_string(), slot],
+            )?;
+        }
+    }
+
+    Ok(())
+}
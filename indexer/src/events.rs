@@ -0,0 +1,71 @@
+use anchor_lang::event::EVENT_IX_TAG_LE;
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use shitcoin_raffle::events::*;
+
+
+/*
+ * Events of the program the indexer understands
+ */
+
+pub enum RaffleEvent {
+    RaffleCreated(RaffleCreated),
+    RaffleUpdated(RaffleUpdated),
+    EndTimestampChanged(EndTimestampChanged),
+    StatusChanged(StatusChanged),
+    TicketsPurchased(TicketsPurchased),
+    RewardSet(RewardSet),
+    ProceedsClaimed(ProceedsClaimed),
+    UnclaimedSwept(UnclaimedSwept),
+    RaffleCancelled(RaffleCancelled),
+    RaffleClosed(RaffleClosed),
+    EntrantCreated(EntrantCreated),
+    EntrantCancelled(EntrantCancelled),
+    ShortenOptInChanged(ShortenOptInChanged),
+    EntrantRefunded(EntrantRefunded),
+    EntrantClosed(EntrantClosed),
+    ReferrerCreated(ReferrerCreated),
+    ReferrerClosed(ReferrerClosed),
+}
+
+
+fn decode<T: AnchorDeserialize + Discriminator>(discriminator: &[u8], data: &[u8]) -> Option<T> {
+    if discriminator != T::discriminator() {
+        return None;
+    }
+
+    T::try_from_slice(data).ok()
+}
+
+
+impl RaffleEvent {
+
+    // Decodes the data of an event CPI instruction, None for any other instruction or events of
+    // the program config, which are not indexed
+    pub fn decode(ix_data: &[u8]) -> Option<Self> {
+        let data = ix_data.strip_prefix(EVENT_IX_TAG_LE)?;
+        if data.len() < 8 {
+            return None;
+        }
+
+        let (discriminator, data) = data.split_at(8);
+
+        None
+            .or_else(|| decode(discriminator, data).map(RaffleEvent::RaffleCreated))
+            .or_else(|| decode(discriminator, data).map(RaffleEvent::RaffleUpdated))
+            .or_else(|| decode(discriminator, data).map(RaffleEvent::EndTimestampChanged))
+            .or_else(|| decode(discriminator, data).map(RaffleEvent::StatusChanged))
+            .or_else(|| decode(discriminator, data).map(RaffleEvent::TicketsPurchased))
+            .or_else(|| decode(discriminator, data).map(RaffleEvent::RewardSet))
+            .or_else(|| decode(discriminator, data).map(RaffleEvent::ProceedsClaimed))
+            .or_else(|| decode(discriminator, data).map(RaffleEvent::UnclaimedSwept))
+            .or_else(|| decode(discriminator, data).map(RaffleEvent::RaffleCancelled))
+            .or_else(|| decode(discriminator, data).map(RaffleEvent::RaffleClosed))
+            .or_else(|| decode(discriminator, data).map(RaffleEvent::EntrantCreated))
+            .or_else(|| decode(discriminator, data).map(RaffleEvent::EntrantCancelled))
+            .or_else(|| decode(discriminator, data).map(RaffleEvent::ShortenOptInChanged))
+            .or_else(|| decode(discriminator, data).map(RaffleEvent::EntrantRefunded))
+            .or_else(|| decode(discriminator, data).map(RaffleEvent::EntrantClosed))
+            .or_else(|| decode(discriminator, data).map(RaffleEvent::ReferrerCreated))
+            .or_else(|| decode(discriminator, data).map(RaffleEvent::ReferrerClosed))
+    }
+}
@@ -0,0 +1,74 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+
+/*
+ * Transaction as seen by the indexer, written by `follow --record` and read back by `replay`
+ */
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RecordedTransaction {
+
+    // Transaction signature
+    pub signature: String,
+
+    // Slot the transaction was processed in
+    pub slot: u64,
+
+    // Position of the transaction within its slot, in execution order
+    #[serde(default)]
+    pub index: u64,
+
+    // Block time, if known
+    pub block_time: Option<i64>,
+
+    // Inner instructions of the transaction, in execution order
+    pub inner_instructions: Vec<RecordedInstruction>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RecordedInstruction {
+
+    // Invoked program
+    pub program_id: String,
+
+    // Instruction data, base58 encoded
+    pub data: String,
+}
+
+
+impl RecordedTransaction {
+
+    pub fn write(&self, dir: &Path) -> Result<()> {
+        let path = dir.join(format!("{:012}-{:04}-{}.json", self.slot, self.index, self.signature));
+        let contents = serde_json::to_string_pretty(self)?;
+
+        fs::write(&path, contents).with_context(|| format!("writing {}", path.display()))
+    }
+
+    // All transactions recorded in a directory, in execution order
+    pub fn read_dir(dir: &Path) -> Result<Vec<RecordedTransaction>> {
+        let mut paths = Vec::new();
+        for entry in fs::read_dir(dir).with_context(|| format!("reading {}", dir.display()))? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) == Some("json") {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+
+        let mut transactions = Vec::with_capacity(paths.len());
+        for path in paths {
+            let contents = fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+            let tx: RecordedTransaction = serde_json::from_str(&contents)
+                .with_context(|| format!("parsing {}", path.display()))?;
+            transactions.push(tx);
+        }
+
+        transactions.sort_by_key(|tx| (tx.slot, tx.index));
+        Ok(transactions)
+    }
+}
@@ -0,0 +1,7 @@
+pub mod db;
+pub mod events;
+pub mod fixture;
+pub mod rpc;
+
+pub use db::{Database, RaffleCounters};
+pub use fixture::{RecordedInstruction, RecordedTransaction};
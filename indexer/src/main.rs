@@ -0,0 +1,83 @@
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use raffle_indexer::rpc::Source;
+use raffle_indexer::{Database, RecordedTransaction};
+
+
+#[derive(Parser)]
+#[command(name = "raffle-indexer", about = "Index shitcoin_raffle events into SQLite")]
+struct Cli {
+
+    /// SQLite database, created if it does not exist
+    #[arg(long, default_value = "raffles.db")]
+    db: PathBuf,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+
+    /// Follow the program's transactions on an RPC endpoint
+    Follow {
+        /// RPC endpoint, defaults to a local solana-test-validator
+        #[arg(long, short = 'u', default_value = "http://127.0.0.1:8899")]
+        url: String,
+
+        /// Seconds between polls
+        #[arg(long, default_value_t = 5)]
+        interval: u64,
+
+        /// Also write every fetched transaction to this directory, for later replay
+        #[arg(long)]
+        record: Option<PathBuf>,
+    },
+
+    /// Index transactions previously recorded with `follow --record`
+    Replay {
+        dir: PathBuf,
+    },
+}
+
+
+fn main() -> Result<()> {
+
+    let cli = Cli::parse();
+    let mut db = Database::open(&cli.db)?;
+
+    match cli.command {
+        Command::Follow { url, interval, record } => {
+            let source = Source::new(url);
+            loop {
+                let last_signature = db.last_signature()?;
+                for tx in source.transactions_since(last_signature.as_deref())? {
+                    if let Some(dir) = &record {
+                        tx.write(dir)?;
+                    }
+
+                    if db.apply(&tx)? {
+                        println!("{} slot {}", tx.signature, tx.slot);
+                    }
+                }
+
+                thread::sleep(Duration::from_secs(interval));
+            }
+        }
+        Command::Replay { dir } => {
+            let mut indexed = 0;
+            for tx in RecordedTransaction::read_dir(&dir)? {
+                if db.apply(&tx)? {
+                    indexed += 1;
+                }
+            }
+
+            println!("indexed {} transactions", indexed);
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context, Result};
+use solana_client::rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient};
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::option_serializer::OptionSerializer;
+use solana_transaction_status::{EncodedConfirmedTransactionWithStatusMeta, UiInstruction, UiTransactionEncoding};
+use shitcoin_raffle::ID;
+
+use crate::fixture::{RecordedInstruction, RecordedTransaction};
+
+// Maximum number of signatures returned by a single getSignaturesForAddress call
+const SIGNATURES_PAGE: usize = 1_000;
+
+
+/*
+ * Fetches the program's transactions from an RPC endpoint
+ */
+
+pub struct Source {
+    rpc: RpcClient,
+}
+
+
+impl Source {
+
+    pub fn new(url: String) -> Self {
+        Source {
+            rpc: RpcClient::new_with_commitment(url, CommitmentConfig::confirmed()),
+        }
+    }
+
+    // Successful transactions after `until`, oldest first
+    pub fn transactions_since(&self, until: Option<&str>) -> Result<Vec<RecordedTransaction>> {
+        let until = until.map(Signature::from_str).transpose().context("parsing last signature")?;
+
+        // Signatures are returned newest first, page backwards until `until` is reached
+        let mut signatures = Vec::new();
+        let mut before = None;
+        loop {
+            let page = self.rpc
+                .get_signatures_for_address_with_config(
+                    &ID,
+                    GetConfirmedSignaturesForAddress2Config {
+                        before,
+                        until,
+                        limit: Some(SIGNATURES_PAGE),
+                        commitment: Some(CommitmentConfig::confirmed()),
+                    },
+                )
+                .context("fetching signatures")?;
+
+            let done = page.len() < SIGNATURES_PAGE;
+            before = page.last().map(|status| Signature::from_str(&status.signature)).transpose()?;
+            signatures.extend(page.into_iter().filter(|status| status.err.is_none()));
+
+            if done {
+                break;
+            }
+        }
+
+        // Within a slot the signatures are newest first too, so the reversed position is the
+        // execution order
+        let mut transactions = Vec::with_capacity(signatures.len());
+        let mut previous_slot = None;
+        let mut index = 0;
+        for status in signatures.iter().rev() {
+            index = if previous_slot == Some(status.slot) { index + 1 } else { 0 };
+            previous_slot = Some(status.slot);
+
+            let signature = Signature::from_str(&status.signature)?;
+            transactions.push(self.transaction(&signature, index)?);
+        }
+
+        Ok(transactions)
+    }
+
+    fn transaction(&self, signature: &Signature, index: u64) -> Result<RecordedTransaction> {
+        let tx = self.rpc
+            .get_transaction_with_config(
+                signature,
+                RpcTransactionConfig {
+                    encoding: Some(UiTransactionEncoding::Base64),
+                    commitment: Some(CommitmentConfig::confirmed()),
+                    max_supported_transaction_version: Some(0),
+                },
+            )
+            .with_context(|| format!("fetching transaction {}", signature))?;
+
+        record(signature, index, tx)
+    }
+}
+
+
+fn record(signature: &Signature, index: u64, tx: EncodedConfirmedTransactionWithStatusMeta) -> Result<RecordedTransaction> {
+    let meta = tx.transaction.meta.ok_or_else(|| anyhow!("transaction {} has no metadata", signature))?;
+    let decoded = tx.transaction.transaction.decode()
+        .ok_or_else(|| anyhow!("transaction {} could not be decoded", signature))?;
+
+    // Static keys followed by the keys loaded from lookup tables, as indexed by the instructions
+    let mut account_keys: Vec<String> = decoded.message.static_account_keys()
+        .iter()
+        .map(|key| key.to_string())
+        .collect();
+    if let OptionSerializer::Some(loaded) = &meta.loaded_addresses {
+        account_keys.extend(loaded.writable.iter().cloned());
+        account_keys.extend(loaded.readonly.iter().cloned());
+    }
+
+    let mut inner_instructions = Vec::new();
+    if let OptionSerializer::Some(inner) = meta.inner_instructions {
+        for group in inner {
+            for ix in group.instructions {
+                if let UiInstruction::Compiled(ix) = ix {
+                    let program_id = account_keys
+                        .get(ix.program_id_index as usize)
+                        .cloned()
+                        .ok_or_else(|| anyhow!("transaction {} has an invalid program index", signature))?;
+
+                    inner_instructions.push(RecordedInstruction { program_id, data: ix.data });
+                }
+            }
+        }
+    }
+
+    Ok(RecordedTransaction {
+        signature: signature.to_string(),
+        slot: tx.slot,
+        index,
+        block_time: tx.block_time,
+        inner_instructions,
+    })
+}
@@ -0,0 +1,97 @@
+use std::path::PathBuf;
+
+use raffle_indexer::{Database, RaffleCounters, RecordedTransaction};
+
+// Raffle created in the recorded fixtures
+const RAFFLE: &str = "831WKuBPV9EkmyT8V4x16rNkHSppAUHaDnrdsDwfB9oY";
+
+
+fn fixtures() -> Vec<RecordedTransaction> {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures");
+    RecordedTransaction::read_dir(&dir).unwrap()
+}
+
+fn replay() -> Database {
+    let mut db = Database::open_in_memory().unwrap();
+    for tx in fixtures() {
+        assert!(db.apply(&tx).unwrap());
+    }
+
+    db
+}
+
+fn count(db: &Database, sql: &str) -> i64 {
+    db.connection().query_row(sql, [], |row| row.get(0)).unwrap()
+}
+
+
+#[test]
+fn replay_rebuilds_raffle_counters() {
+    let db = replay();
+
+    let raffles = db.raffles().unwrap();
+    assert_eq!(raffles.len(), 1);
+    assert_eq!(raffles[0].to_string(), RAFFLE);
+
+    // Raffle of 100 @ 100 with a fee of 10, two buyers, one referral and both rewards claimed
+    assert_eq!(
+        db.raffle_counters(&raffles[0]).unwrap(),
+        RaffleCounters {
+            end_timestamp: 2060,
+            tickets_sold: 5,
+            entrants: 2,
+            shorten_opt_ins: 0,
+            rewards_awarded: 2,
+            rewards_claimed: 2,
+            rewards_forfeited: 0,
+            tickets_refunded: 3,
+            referral_accrued: 15,
+            referral_claimed: 15,
+            admin_claimed: true,
+            swept: false,
+        }
+    );
+}
+
+#[test]
+fn replay_records_history() {
+    let db = replay();
+
+    assert_eq!(count(&db, "SELECT COUNT(*) FROM raffles WHERE referral_share = 5000 AND extension_duration = 60"), 1);
+    assert_eq!(count(&db, "SELECT COUNT(*) FROM purchases"), 2);
+    assert_eq!(count(&db, "SELECT COUNT(*) FROM rewards"), 2);
+    assert_eq!(count(&db, "SELECT COUNT(*) FROM claims"), 4);
+    assert_eq!(count(&db, "SELECT COUNT(*) FROM entrants WHERE closed_slot IS NULL"), 0);
+    assert_eq!(count(&db, "SELECT COUNT(*) FROM referrers WHERE closed_slot IS NULL"), 0);
+    assert_eq!(count(&db, "SELECT COUNT(*) FROM raffles WHERE closed_slot IS NOT NULL AND status = 'Settled'"), 1);
+
+    // Vault is emptied by the payouts
+    assert_eq!(count(&db, "SELECT SUM(total_price) FROM purchases"), 500);
+    assert_eq!(count(&db, "SELECT SUM(amount) FROM claims"), 500);
+}
+
+#[test]
+fn replay_keeps_the_execution_order_within_a_slot() {
+    let db = replay();
+
+    // The last reward and the proceeds claim share a slot, the claim's signature sorts first
+    let slot: Vec<_> = fixtures().into_iter().filter(|tx| tx.slot == 18).collect();
+    assert_eq!(slot.iter().map(|tx| tx.index).collect::<Vec<_>>(), [0, 1]);
+    assert!(slot[1].signature < slot[0].signature);
+
+    assert_eq!(count(&db, "SELECT COUNT(*) FROM raffles WHERE status = 'Settled'"), 1);
+}
+
+#[test]
+fn replay_skips_indexed_transactions() {
+    let mut db = replay();
+    let raffle = db.raffles().unwrap()[0];
+    let counters = db.raffle_counters(&raffle).unwrap();
+
+    for tx in fixtures() {
+        assert!(!db.apply(&tx).unwrap());
+    }
+
+    assert_eq!(db.raffle_counters(&raffle).unwrap(), counters);
+    assert_eq!(db.last_signature().unwrap(), fixtures().last().map(|tx| tx.signature.clone()));
+}
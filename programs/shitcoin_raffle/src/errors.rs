@@ -86,4 +86,100 @@ pub enum RaffleError {
     // 6020
     #[msg("Entrant's reward has already been set yet")]
     EntrantAlreadyAwarded,
+
+    // 6021
+    #[msg("Referral share must not exceed 10000 basis points")]
+    ReferralShareTooLarge,
+
+    // 6022
+    #[msg("Entrant can not refer themselves")]
+    SelfReferral,
+
+    // 6023
+    #[msg("Not all referral rewards have been claimed yet")]
+    RaffleReferralsNotClaimed,
+
+    // 6024
+    #[msg("Signer has not been proposed as the new admin")]
+    AdminNotProposed,
+
+    // 6025
+    #[msg("Program is paused")]
+    ProgramPaused,
+
+    // 6026
+    #[msg("Raffle is paused")]
+    RafflePaused,
+
+    // 6027
+    #[msg("Raffle has already started")]
+    RaffleAlreadyStarted,
+
+    // 6028
+    #[msg("End timestamp would exceed the maximum allowed extension")]
+    ExtensionTooLarge,
+
+    // 6029
+    #[msg("End timestamp can not be shortened without the consent of all entrants")]
+    ShorteningNotAllowed,
+
+    // 6030
+    #[msg("Entrant has not bought any tickets")]
+    EntrantHasNoTickets,
+
+    // 6031
+    #[msg("Extension window, duration and maximum must not be negative")]
+    InvalidExtension,
+
+    // 6032
+    #[msg("Raffle status does not allow this operation")]
+    InvalidRaffleStatus,
+
+    // 6033
+    #[msg("Raffle status transition is not allowed")]
+    InvalidStatusTransition,
+
+    // 6034
+    #[msg("Raffle already has tickets sold")]
+    RaffleHasTicketsSold,
+
+    // 6035
+    #[msg("Claim period must not be negative")]
+    InvalidClaimPeriod,
+
+    // 6036
+    #[msg("Claim deadline has not passed yet")]
+    ClaimDeadlineNotPassed,
+
+    // 6037
+    #[msg("Unclaimed funds have already been swept")]
+    RaffleAlreadySwept,
+
+    // 6038
+    #[msg("Not all refunds have been claimed yet")]
+    RaffleRefundsNotClaimed,
+
+    // 6039
+    #[msg("Settlement accounts do not match the entrant")]
+    InvalidSettlementAccounts,
+
+    // 6040
+    #[msg("Entrant has already bought tickets")]
+    EntrantHasTickets,
+
+    // 6041
+    #[msg("Account can not be migrated by this instruction")]
+    InvalidMigrationAccount,
+
+    // 6042
+    #[msg("Account has already been migrated")]
+    AccountAlreadyMigrated,
+
+    // 6043
+    #[msg("Proceeds balance is below the raffle's liabilities")]
+    VaultInsolvent,
+
+    // 6044
+    #[msg("Maximum extension must not be negative")]
+    InvalidMaxExtension,
 }
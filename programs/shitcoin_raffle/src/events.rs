@@ -0,0 +1,451 @@
+use anchor_lang::prelude::*;
+
+use crate::states::*;
+
+
+/*
+ * Program config events
+ */
+
+#[event]
+pub struct AdminChanged {
+
+    // Admin before the change
+    pub old_admin: Pubkey,
+
+    // Admin after the change
+    pub new_admin: Pubkey,
+}
+
+#[event]
+pub struct AdminProposed {
+
+    // Proposed admin, none if the proposal was cancelled
+    pub pending_admin: Option<Pubkey>,
+}
+
+#[event]
+pub struct CreationFeeChanged {
+
+    // Fee in lamports paid for creating a raffle
+    pub creation_fee: u64,
+}
+
+#[event]
+pub struct MaxExtensionChanged {
+
+    // Maximum number of seconds a raffle's end timestamp can be extended by
+    pub max_extension: i64,
+}
+
+#[event]
+pub struct RoleChanged {
+
+    // Role that was granted or revoked
+    pub role: Role,
+
+    // Holder of the role, default pubkey if the role was revoked
+    pub key: Pubkey,
+}
+
+#[event]
+pub struct PauseChanged {
+
+    // Raffle account, none for the global pause
+    pub raffle: Option<Pubkey>,
+
+    // Paused after the change
+    pub paused: bool,
+}
+
+#[event]
+pub struct AccountMigrated {
+
+    // Migrated account
+    pub account: Pubkey,
+
+    // Version of the account layout after the migration
+    pub version: u8,
+}
+
+
+/*
+ * Raffle events
+ */
+
+#[event]
+pub struct RaffleCreated {
+
+    // Raffle account
+    pub raffle: Pubkey,
+
+    // Seed used in deriving the raffle account
+    pub seed: Pubkey,
+
+    // Creator of the raffle
+    pub creator: Pubkey,
+
+    // Currency mint
+    pub currency: Pubkey,
+
+    // Reward mint
+    pub reward: Pubkey,
+
+    // Entry price of the raffle
+    pub price: u64,
+
+    // Non-refundable fee
+    pub fee: u64,
+
+    // Number of rewards
+    pub rewards_num: u64,
+
+    // Reward amount
+    pub rewards_amount: u64,
+
+    // Raffle start timestamp
+    pub start_timestamp: i64,
+
+    // Raffle end timestamp
+    pub end_timestamp: i64,
+
+    // Number of tickets
+    pub tickets: Option<u64>,
+
+    // Maximum number of tickets that can be purchased by single user
+    pub limit: Option<u64>,
+
+    // Share of the fee paid to referrers, in basis points
+    pub referral_share: u16,
+
+    // Purchases within this many seconds before the end extend the raffle
+    pub extension_window: i64,
+
+    // Number of seconds the raffle is extended by on a late purchase
+    pub extension_duration: i64,
+
+    // Number of seconds late purchases can extend the raffle past its initial end timestamp
+    pub max_auto_extension: i64,
+
+    // Number of seconds entrants have to claim after the rewards are set
+    pub claim_period: Option<i64>,
+
+    // Creation fee paid to the treasury
+    pub creation_fee: u64,
+}
+
+#[event]
+pub struct RaffleUpdated {
+
+    // Raffle account
+    pub raffle: Pubkey,
+
+    // Entry price of the raffle
+    pub price: u64,
+
+    // Non-refundable fee
+    pub fee: u64,
+
+    // Number of rewards
+    pub rewards_num: u64,
+
+    // Reward amount
+    pub rewards_amount: u64,
+
+    // Raffle start timestamp
+    pub start_timestamp: i64,
+
+    // Raffle end timestamp
+    pub end_timestamp: i64,
+
+    // Number of tickets
+    pub tickets: Option<u64>,
+
+    // Maximum number of tickets that can be purchased by single user
+    pub limit: Option<u64>,
+
+    // Share of the fee paid to referrers, in basis points
+    pub referral_share: u16,
+
+    // Purchases within this many seconds before the end extend the raffle
+    pub extension_window: i64,
+
+    // Number of seconds the raffle is extended by on a late purchase
+    pub extension_duration: i64,
+
+    // Number of seconds late purchases can extend the raffle past its initial end timestamp
+    pub max_auto_extension: i64,
+
+    // Number of seconds entrants have to claim after the rewards are set
+    pub claim_period: Option<i64>,
+}
+
+#[event]
+pub struct EndTimestampChanged {
+
+    // Raffle account
+    pub raffle: Pubkey,
+
+    // End timestamp before the change
+    pub old_end_timestamp: i64,
+
+    // End timestamp after the change
+    pub new_end_timestamp: i64,
+}
+
+#[event]
+pub struct StatusChanged {
+
+    // Raffle account
+    pub raffle: Pubkey,
+
+    // Status before the change
+    pub old_status: RaffleStatus,
+
+    // Status after the change
+    pub new_status: RaffleStatus,
+}
+
+#[event]
+pub struct TicketsPurchased {
+
+    // Raffle account
+    pub raffle: Pubkey,
+
+    // User
+    pub user: Pubkey,
+
+    // Number of tickets bought
+    pub amount: u64,
+
+    // Price paid for the tickets
+    pub total_price: u64,
+
+    // Number of tickets sold after the purchase
+    pub tickets_sold: u64,
+
+    // Referrer account, if any
+    pub referrer: Option<Pubkey>,
+
+    // Referral rewards accrued by the purchase
+    pub referral_amount: u64,
+}
+
+#[event]
+pub struct RewardSet {
+
+    // Raffle account
+    pub raffle: Pubkey,
+
+    // User
+    pub user: Pubkey,
+
+    // Number of winning tickets
+    pub amount: u64,
+
+    // Number of awarded rewards after the change
+    pub rewards_awarded: u64,
+}
+
+#[event]
+pub struct ProceedsClaimed {
+
+    // Raffle account
+    pub raffle: Pubkey,
+
+    // Creator of the raffle
+    pub creator: Pubkey,
+
+    // Amount of proceeds transferred to the creator
+    pub amount: u64,
+}
+
+#[event]
+pub struct UnclaimedSwept {
+
+    // Raffle account
+    pub raffle: Pubkey,
+
+    // Treasury account
+    pub treasury: Pubkey,
+
+    // Amount of unclaimed refunds and referral rewards transferred to the treasury
+    pub amount: u64,
+
+    // Number of rewards forfeited
+    pub rewards_forfeited: u64,
+}
+
+#[event]
+pub struct ExcessSwept {
+
+    // Raffle account
+    pub raffle: Pubkey,
+
+    // Token account owned by the raffle
+    pub vault: Pubkey,
+
+    // Mint of the token account
+    pub mint: Pubkey,
+
+    // Treasury account
+    pub treasury: Pubkey,
+
+    // Amount above the raffle's liabilities transferred to the treasury
+    pub amount: u64,
+}
+
+#[event]
+pub struct SolvencyVerified {
+
+    // Raffle account
+    pub raffle: Pubkey,
+
+    // Proceeds owed in refunds, referral rewards and to the creator
+    pub liabilities: u64,
+
+    // Proceeds token account balance
+    pub balance: u64,
+}
+
+#[event]
+pub struct RaffleCancelled {
+
+    // Raffle account
+    pub raffle: Pubkey,
+}
+
+#[event]
+pub struct RaffleClosed {
+
+    // Raffle account
+    pub raffle: Pubkey,
+
+    // Creator of the raffle
+    pub creator: Pubkey,
+}
+
+
+/*
+ * Entrant events
+ */
+
+#[event]
+pub struct EntrantCreated {
+
+    // Raffle account
+    pub raffle: Pubkey,
+
+    // Entrant account
+    pub entrant: Pubkey,
+
+    // User
+    pub user: Pubkey,
+}
+
+#[event]
+pub struct EntrantCancelled {
+
+    // Raffle account
+    pub raffle: Pubkey,
+
+    // Entrant account
+    pub entrant: Pubkey,
+
+    // User
+    pub user: Pubkey,
+}
+
+#[event]
+pub struct ShortenOptInChanged {
+
+    // Raffle account
+    pub raffle: Pubkey,
+
+    // User
+    pub user: Pubkey,
+
+    // Entrant agreed to the end timestamp being shortened
+    pub opt_in: bool,
+}
+
+#[event]
+pub struct EntrantRefunded {
+
+    // Raffle account
+    pub raffle: Pubkey,
+
+    // Entrant account
+    pub entrant: Pubkey,
+
+    // User
+    pub user: Pubkey,
+
+    // Number of losing tickets refunded, the entrant keeps its winning tickets
+    pub tickets: u64,
+
+    // Amount of proceeds refunded
+    pub refund_amount: u64,
+}
+
+#[event]
+pub struct EntrantClosed {
+
+    // Raffle account
+    pub raffle: Pubkey,
+
+    // Entrant account
+    pub entrant: Pubkey,
+
+    // User
+    pub user: Pubkey,
+
+    // Number of entry tickets
+    pub tickets: u64,
+
+    // Number of winning tickets
+    pub rewards: u64,
+
+    // Amount of proceeds refunded
+    pub refund_amount: u64,
+
+    // Amount of reward tokens minted
+    pub reward_amount: u64,
+
+    // Unclaimed funds had been swept, nothing was paid out
+    pub swept: bool,
+}
+
+
+/*
+ * Referrer events
+ */
+
+#[event]
+pub struct ReferrerCreated {
+
+    // Raffle account
+    pub raffle: Pubkey,
+
+    // Referrer account
+    pub referrer: Pubkey,
+
+    // User
+    pub user: Pubkey,
+}
+
+#[event]
+pub struct ReferrerClosed {
+
+    // Raffle account
+    pub raffle: Pubkey,
+
+    // Referrer account
+    pub referrer: Pubkey,
+
+    // User
+    pub user: Pubkey,
+
+    // Amount of referral rewards paid out
+    pub amount: u64,
+}
@@ -1,6 +1,8 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::bpf_loader_upgradeable;
 
-use crate::program::ShitcoinRaffle;
+use crate::errors::*;
+use crate::events::*;
 use crate::states::*;
 
 
@@ -9,6 +11,7 @@ use crate::states::*;
  */
 
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct InitAdmin<'info> {
 
@@ -18,18 +21,15 @@ pub struct InitAdmin<'info> {
         seeds = [b"admin".as_ref()], 
         bump, 
         payer = authority,
-        space = 8 + std::mem::size_of::<AdminSettings>(),
+        space = AdminSettings::SPACE,
     )]
     pub admin_settings: Account<'info, AdminSettings>,
     
-    // ShitcoinRaffle program
-    #[account(
-        constraint = program.programdata_address()? == Some(program_data.key())
-    )]
-    pub program: Program<'info, ShitcoinRaffle>,
-    
     // ShitcoinRaffle program data
     #[account(
+        seeds = [crate::ID.as_ref()],
+        bump,
+        seeds::program = bpf_loader_upgradeable::ID,
         constraint = program_data.upgrade_authority_address == Some(authority.key())
     )]
     pub program_data: Account<'info, ProgramData>,
@@ -46,21 +46,36 @@ pub struct InitAdmin<'info> {
 pub fn init_admin(ctx: Context<InitAdmin>, admin: Pubkey) -> Result<()> {
 
     let admin_settings = &mut ctx.accounts.admin_settings;
+    admin_settings.version = AdminSettings::VERSION;
     admin_settings.bump = *ctx.bumps.get("admin_settings").unwrap();
     admin_settings.admin = admin;
+    admin_settings.pending_admin = None;
+    admin_settings.creation_fee = 0;
+    admin_settings.max_extension = 0;
+    admin_settings.creator = Pubkey::default();
+    admin_settings.drawer = Pubkey::default();
+    admin_settings.treasurer = Pubkey::default();
+    admin_settings.pauser = Pubkey::default();
+    admin_settings.paused = false;
     
+    emit_cpi!(AdminChanged {
+        old_admin: Pubkey::default(),
+        new_admin: admin,
+    });
+
     Ok(())
 }
 
 
 
 /*
- *  Set admin by updating the AdminSettings account
+ *  Propose a new admin by updating the AdminSettings account
  */
 
 
+#[event_cpi]
 #[derive(Accounts)]
-pub struct SetAdmin<'info> {
+pub struct ProposeAdmin<'info> {
 
     // AdminSettings account
     #[account(
@@ -70,14 +85,146 @@ pub struct SetAdmin<'info> {
     )]
     pub admin_settings: Account<'info, AdminSettings>,
     
-    // ShitcoinRaffle program
+    // ShitcoinRaffle program data
+    #[account(
+        seeds = [crate::ID.as_ref()],
+        bump,
+        seeds::program = bpf_loader_upgradeable::ID,
+        constraint = program_data.upgrade_authority_address == Some(authority.key())
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    
+    // Authority for updating the AdminSettings account -> upgrade authority of the ShitcoinRaffle program
+    #[account(mut)]
+    pub authority: Signer<'info>,
+} 
+
+
+pub fn propose_admin(ctx: Context<ProposeAdmin>, admin: Pubkey) -> Result<()> {
+
+    let admin_settings = &mut ctx.accounts.admin_settings;
+    admin_settings.pending_admin = Some(admin);
+    
+    emit_cpi!(AdminProposed {
+        pending_admin: Some(admin),
+    });
+
+    Ok(())
+}
+
+
+
+/*
+ *  Accept the admin proposal, signed by the proposed admin
+ */
+
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+
+    // AdminSettings account
+    #[account(
+        mut,
+        seeds = [b"admin".as_ref()],
+        bump,
+        constraint = admin_settings.pending_admin == Some(admin.key()) @ RaffleError::AdminNotProposed,
+    )]
+    pub admin_settings: Account<'info, AdminSettings>,
+    
+    // Proposed admin account
+    pub admin: Signer<'info>,
+} 
+
+
+pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+
+    let admin_settings = &mut ctx.accounts.admin_settings;
+    let old_admin = admin_settings.admin;
+    admin_settings.admin = ctx.accounts.admin.key();
+    admin_settings.pending_admin = None;
+
+    emit_cpi!(AdminChanged {
+        old_admin,
+        new_admin: admin_settings.admin,
+    });
+    
+    Ok(())
+}
+
+
+
+/*
+ *  Cancel a pending admin proposal
+ */
+
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelAdmin<'info> {
+
+    // AdminSettings account
+    #[account(
+        mut,
+        seeds = [b"admin".as_ref()],
+        bump,
+    )]
+    pub admin_settings: Account<'info, AdminSettings>,
+    
+    // ShitcoinRaffle program data
+    #[account(
+        seeds = [crate::ID.as_ref()],
+        bump,
+        seeds::program = bpf_loader_upgradeable::ID,
+        constraint = program_data.upgrade_authority_address == Some(authority.key())
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    
+    // Authority for updating the AdminSettings account -> upgrade authority of the ShitcoinRaffle program
+    #[account(mut)]
+    pub authority: Signer<'info>,
+} 
+
+
+pub fn cancel_admin(ctx: Context<CancelAdmin>) -> Result<()> {
+
+    let admin_settings = &mut ctx.accounts.admin_settings;
+    admin_settings.pending_admin = None;
+    
+    emit_cpi!(AdminProposed {
+        pending_admin: None,
+    });
+
+    Ok(())
+}
+
+
+
+/*
+ *  Set admin directly, signed by the upgrade authority
+ *
+ *  Deprecated in favour of propose_admin and accept_admin, kept for existing callers and as an
+ *  escape hatch when the proposed admin can not sign. Clears any pending proposal.
+ */
+
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetAdmin<'info> {
+
+    // AdminSettings account
     #[account(
-        constraint = program.programdata_address()? == Some(program_data.key())
+        mut,
+        seeds = [b"admin".as_ref()],
+        bump,
     )]
-    pub program: Program<'info, ShitcoinRaffle>,
+    pub admin_settings: Account<'info, AdminSettings>,
     
     // ShitcoinRaffle program data
     #[account(
+        seeds = [crate::ID.as_ref()],
+        bump,
+        seeds::program = bpf_loader_upgradeable::ID,
         constraint = program_data.upgrade_authority_address == Some(authority.key())
     )]
     pub program_data: Account<'info, ProgramData>,
@@ -91,7 +238,243 @@ pub struct SetAdmin<'info> {
 pub fn set_admin(ctx: Context<SetAdmin>, admin: Pubkey) -> Result<()> {
 
     let admin_settings = &mut ctx.accounts.admin_settings;
+    let old_admin = admin_settings.admin;
     admin_settings.admin = admin;
+    admin_settings.pending_admin = None;
+    
+    emit_cpi!(AdminChanged {
+        old_admin,
+        new_admin: admin,
+    });
+
+    Ok(())
+}
+
+
+
+/*
+ *  Set the raffle creation fee by updating the AdminSettings account
+ */
+
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetCreationFee<'info> {
+
+    // AdminSettings account
+    #[account(
+        mut,
+        seeds = [b"admin".as_ref()],
+        bump,
+    )]
+    pub admin_settings: Account<'info, AdminSettings>,
+    
+    // ShitcoinRaffle program data
+    #[account(
+        seeds = [crate::ID.as_ref()],
+        bump,
+        seeds::program = bpf_loader_upgradeable::ID,
+        constraint = program_data.upgrade_authority_address == Some(authority.key())
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    
+    // Authority for updating the AdminSettings account -> upgrade authority of the ShitcoinRaffle program
+    #[account(mut)]
+    pub authority: Signer<'info>,
+} 
+
+
+pub fn set_creation_fee(ctx: Context<SetCreationFee>, creation_fee: u64) -> Result<()> {
+
+    let admin_settings = &mut ctx.accounts.admin_settings;
+    admin_settings.creation_fee = creation_fee;
+    
+    emit_cpi!(CreationFeeChanged {
+        creation_fee,
+    });
+
+    Ok(())
+}
+
+
+
+/*
+ *  Set the maximum end timestamp extension by updating the AdminSettings account
+ */
+
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetMaxExtension<'info> {
+
+    // AdminSettings account
+    #[account(
+        mut,
+        seeds = [b"admin".as_ref()],
+        bump,
+    )]
+    pub admin_settings: Account<'info, AdminSettings>,
+    
+    // ShitcoinRaffle program data
+    #[account(
+        seeds = [crate::ID.as_ref()],
+        bump,
+        seeds::program = bpf_loader_upgradeable::ID,
+        constraint = program_data.upgrade_authority_address == Some(authority.key())
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    
+    // Authority for updating the AdminSettings account -> upgrade authority of the ShitcoinRaffle program
+    #[account(mut)]
+    pub authority: Signer<'info>,
+} 
+
+
+pub fn set_max_extension(ctx: Context<SetMaxExtension>, max_extension: i64) -> Result<()> {
+
+    require!(
+        max_extension >= 0,
+        RaffleError::InvalidMaxExtension
+    );
+
+    let admin_settings = &mut ctx.accounts.admin_settings;
+    admin_settings.max_extension = max_extension;
+    
+    emit_cpi!(MaxExtensionChanged {
+        max_extension,
+    });
+
+    Ok(())
+}
+
+
+
+/*
+ *  Grant a role by updating the AdminSettings account
+ */
+
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct GrantRole<'info> {
+
+    // AdminSettings account
+    #[account(
+        mut,
+        seeds = [b"admin".as_ref()],
+        bump,
+    )]
+    pub admin_settings: Account<'info, AdminSettings>,
+    
+    // ShitcoinRaffle program data
+    #[account(
+        seeds = [crate::ID.as_ref()],
+        bump,
+        seeds::program = bpf_loader_upgradeable::ID,
+        constraint = program_data.upgrade_authority_address == Some(authority.key())
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    
+    // Authority for updating the AdminSettings account -> upgrade authority of the ShitcoinRaffle program
+    #[account(mut)]
+    pub authority: Signer<'info>,
+} 
+
+
+pub fn grant_role(ctx: Context<GrantRole>, role: Role, key: Pubkey) -> Result<()> {
+
+    let admin_settings = &mut ctx.accounts.admin_settings;
+    admin_settings.set_role(role, key);
     
+    emit_cpi!(RoleChanged {
+        role,
+        key,
+    });
+
+    Ok(())
+}
+
+
+
+/*
+ *  Revoke a role by updating the AdminSettings account
+ */
+
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RevokeRole<'info> {
+
+    // AdminSettings account
+    #[account(
+        mut,
+        seeds = [b"admin".as_ref()],
+        bump,
+    )]
+    pub admin_settings: Account<'info, AdminSettings>,
+    
+    // ShitcoinRaffle program data
+    #[account(
+        seeds = [crate::ID.as_ref()],
+        bump,
+        seeds::program = bpf_loader_upgradeable::ID,
+        constraint = program_data.upgrade_authority_address == Some(authority.key())
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    
+    // Authority for updating the AdminSettings account -> upgrade authority of the ShitcoinRaffle program
+    #[account(mut)]
+    pub authority: Signer<'info>,
+} 
+
+
+pub fn revoke_role(ctx: Context<RevokeRole>, role: Role) -> Result<()> {
+
+    let admin_settings = &mut ctx.accounts.admin_settings;
+    admin_settings.set_role(role, Pubkey::default());
+    
+    emit_cpi!(RoleChanged {
+        role,
+        key: Pubkey::default(),
+    });
+
+    Ok(())
+}
+
+
+
+/*
+ *  Pause or unpause the program by updating the AdminSettings account
+ */
+
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+
+    // AdminSettings account
+    #[account(
+        mut,
+        seeds = [b"admin".as_ref()],
+        bump,
+    )]
+    pub admin_settings: Account<'info, AdminSettings>,
+    
+    // Pauser account
+    #[account(constraint = admin_settings.has_role(Role::Pauser, &authority.key()))]
+    pub authority: Signer<'info>,
+} 
+
+
+pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+
+    let admin_settings = &mut ctx.accounts.admin_settings;
+    admin_settings.paused = paused;
+    
+    emit_cpi!(PauseChanged {
+        raffle: None,
+        paused,
+    });
+
     Ok(())
 }
@@ -6,9 +6,11 @@ use anchor_lang::prelude::*;
 use anchor_spl::{
     token::{Mint, TokenAccount},
     token_2022::{self, Token2022, TransferChecked, MintTo},
-    associated_token::AssociatedToken
+    associated_token::{self, AssociatedToken, get_associated_token_address_with_program_id}
 };
 
+use crate::errors::*;
+use crate::events::*;
 use crate::states::*;
 
 
@@ -16,10 +18,19 @@ use crate::states::*;
  * Initialize a new entrant account
  */
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct InitEntrant<'info> {
+
+    // AdminSettings account
+    #[account(
+        seeds = [b"admin".as_ref()], 
+        bump
+    )]
+    pub admin_settings: Account<'info, AdminSettings>,
  
     // Raffle account
+    #[account(mut)]
     pub raffle: Account<'info, Raffle>,    
  
     // Entrant account
@@ -28,7 +39,7 @@ pub struct InitEntrant<'info> {
        seeds = [b"entrant".as_ref(), raffle.key().as_ref(), user.key().as_ref()],
        bump,
        payer = user,
-       space = 8 + std::mem::size_of::<Entrant>(),
+       space = Entrant::SPACE,
     )]
     pub entrant: Account<'info, Entrant>,
 
@@ -42,17 +53,81 @@ pub struct InitEntrant<'info> {
  
 pub fn initialize(ctx: Context<InitEntrant>) -> Result<()> {
      
-    let raffle = &ctx.accounts.raffle;
+    let raffle = &mut ctx.accounts.raffle;
     let entrant = &mut ctx.accounts.entrant;
 
+    ctx.accounts.admin_settings.assert_not_paused()?;
+    raffle.assert_not_paused()?;
+    let old_status = raffle.status;
+    raffle.advance()?;
     raffle.assert_active()?;
 
+    entrant.version = Entrant::VERSION;
     entrant.bump = ctx.bumps["entrant"];
     entrant.user = ctx.accounts.user.key();
     entrant.raffle = ctx.accounts.raffle.key();
     entrant.tickets = 0;
     entrant.rewards = 0;
-     
+    entrant.shorten_opt_in = false;
+
+    emit_cpi!(EntrantCreated {
+        raffle: entrant.raffle,
+        entrant: entrant.key(),
+        user: entrant.user,
+    });
+
+    if raffle.status != old_status {
+        emit_cpi!(StatusChanged {
+            raffle: raffle.key(),
+            old_status,
+            new_status: raffle.status,
+        });
+    }
+
+    Ok(())
+}
+
+
+/*
+ * Close an entrant without tickets and return the rent
+ */
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelEntrant<'info> {
+
+    /// CHECK: Only used to derive the entrant account, the raffle may already be closed
+    pub raffle: UncheckedAccount<'info>,
+
+    // Entrant account
+    #[account(
+        mut,
+        seeds = [b"entrant".as_ref(), raffle.key().as_ref(), user.key().as_ref()],
+        bump,
+        close = user,
+    )]
+    pub entrant: Account<'info, Entrant>,
+
+    // User
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
+
+pub fn cancel(ctx: Context<CancelEntrant>) -> Result<()> {
+
+    let entrant = &ctx.accounts.entrant;
+
+    require!(
+        entrant.tickets == 0,
+        RaffleError::EntrantHasTickets
+    );
+
+    emit_cpi!(EntrantCancelled {
+        raffle: ctx.accounts.raffle.key(),
+        entrant: entrant.key(),
+        user: entrant.user,
+    });
+
     Ok(())
 }
 
@@ -61,9 +136,17 @@ pub fn initialize(ctx: Context<InitEntrant>) -> Result<()> {
  * Claim rewards / proceeds and close entrant
  */
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct CloseEntrant<'info> {
 
+    // AdminSettings account
+    #[account(
+        seeds = [b"admin".as_ref()], 
+        bump
+    )]
+    pub admin_settings: Box<Account<'info, AdminSettings>>,
+
     // Raffle account
     #[account(mut)]
     pub raffle: Box<Account<'info, Raffle>>,
@@ -73,7 +156,6 @@ pub struct CloseEntrant<'info> {
         mut,
         seeds = [b"entrant".as_ref(), raffle.key().as_ref(), user.key().as_ref()],
         bump,
-        close = user,
     )]
     pub entrant: Box<Account<'info, Entrant>>,
 
@@ -127,22 +209,297 @@ pub struct CloseEntrant<'info> {
 pub fn close(ctx: Context<CloseEntrant>) -> Result<()> {
 
     let raffle = &mut ctx.accounts.raffle;
-    let entrant = &ctx.accounts.entrant;
-    let currency = &ctx.accounts.currency;
+    let entrant = &mut ctx.accounts.entrant;
+    let tickets = entrant.tickets;
+    let old_status = raffle.status;
+
+    raffle.advance()?;
+    raffle.assert_awarded()?;
+
+    if raffle.status != old_status {
+        emit_cpi!(StatusChanged {
+            raffle: raffle.key(),
+            old_status,
+            new_status: raffle.status,
+        });
+    }
+
+    let (refund_amount, reward_amount, rewards_pending) = pay_out(
+        raffle,
+        entrant,
+        &ctx.accounts.admin_settings,
+        &ctx.accounts.proceeds,
+        &ctx.accounts.currency,
+        ctx.accounts.user_proceeds.to_account_info(),
+        &ctx.accounts.reward,
+        ctx.accounts.user_reward.to_account_info(),
+        &ctx.accounts.token_program,
+    )?;
+
+    if rewards_pending {
+        emit_cpi!(EntrantRefunded {
+            raffle: raffle.key(),
+            entrant: entrant.key(),
+            user: entrant.user,
+            tickets: tickets - entrant.tickets,
+            refund_amount,
+        });
+
+        return Ok(());
+    }
+
+    emit_cpi!(EntrantClosed {
+        raffle: raffle.key(),
+        entrant: entrant.key(),
+        user: entrant.user,
+        tickets: entrant.tickets,
+        rewards: entrant.rewards,
+        refund_amount,
+        reward_amount,
+        swept: raffle.swept,
+    });
+
+    entrant.close(ctx.accounts.user.to_account_info())?;
+
+    Ok(())
+}
+
+
+/*
+ * Settle a batch of entrants, callable by anyone
+ *
+ * Remaining accounts are passed in groups of four per entrant:
+ * [entrant, user, user's proceeds token account, user's reward token account]
+ */
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SettleEntrants<'info> {
+
+    // AdminSettings account
+    #[account(
+        seeds = [b"admin".as_ref()], 
+        bump
+    )]
+    pub admin_settings: Box<Account<'info, AdminSettings>>,
+
+    // Raffle account
+    #[account(mut)]
+    pub raffle: Box<Account<'info, Raffle>>,
+
+    // Proceeds token account
+    #[account(
+        mut,
+        seeds = [b"proceeds".as_ref(), raffle.key().as_ref()],
+        bump,
+        token::mint = currency,
+        token::authority = raffle,
+    )]
+    pub proceeds: Account<'info, TokenAccount>,
+
+    // Proceeds mint
+    pub currency: Account<'info, Mint>,
+
+    // Rewawrd mint
+    #[account(
+        mut,
+        constraint = reward.mint_authority == anchor_lang::solana_program::program_option::COption::Some(raffle.key()),
+    )]
+    pub reward: Account<'info, Mint>,
+
+    // Crank payer, covers the rent of missing token accounts
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    // Token program
+    pub token_program: Program<'info, Token2022>,
+
+    // Associated token program
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    // System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn settle<'info>(ctx: Context<'_, '_, '_, 'info, SettleEntrants<'info>>) -> Result<()> {
 
+    let raffle = &mut ctx.accounts.raffle;
+    let old_status = raffle.status;
+
+    raffle.advance()?;
     raffle.assert_awarded()?;
 
+    if raffle.status != old_status {
+        emit_cpi!(StatusChanged {
+            raffle: raffle.key(),
+            old_status,
+            new_status: raffle.status,
+        });
+    }
+
+    require!(
+        ctx.remaining_accounts.len() % 4 == 0,
+        RaffleError::InvalidSettlementAccounts
+    );
+
+    for accounts in ctx.remaining_accounts.chunks(4) {
+        let entrant_info = &accounts[0];
+        let user = &accounts[1];
+        let user_proceeds = &accounts[2];
+        let user_reward = &accounts[3];
+
+        let mut entrant: Account<'info, Entrant> = Account::try_from(entrant_info)?;
+        let tickets = entrant.tickets;
+
+        require!(
+            entrant.raffle == raffle.key() && entrant.user == user.key(),
+            RaffleError::InvalidSettlementAccounts
+        );
+
+        create_token_account_if_needed(
+            user_proceeds,
+            user,
+            &ctx.accounts.currency,
+            &ctx.accounts.payer,
+            &ctx.accounts.token_program,
+            &ctx.accounts.associated_token_program,
+            &ctx.accounts.system_program,
+        )?;
+
+        create_token_account_if_needed(
+            user_reward,
+            user,
+            &ctx.accounts.reward,
+            &ctx.accounts.payer,
+            &ctx.accounts.token_program,
+            &ctx.accounts.associated_token_program,
+            &ctx.accounts.system_program,
+        )?;
+
+        let (refund_amount, reward_amount, rewards_pending) = pay_out(
+            raffle,
+            &mut entrant,
+            &ctx.accounts.admin_settings,
+            &ctx.accounts.proceeds,
+            &ctx.accounts.currency,
+            user_proceeds.clone(),
+            &ctx.accounts.reward,
+            user_reward.clone(),
+            &ctx.accounts.token_program,
+        )?;
+
+        if rewards_pending {
+            emit_cpi!(EntrantRefunded {
+                raffle: raffle.key(),
+                entrant: entrant.key(),
+                user: entrant.user,
+                tickets: tickets - entrant.tickets,
+                refund_amount,
+            });
+
+            entrant.exit(ctx.program_id)?;
+            continue;
+        }
+
+        emit_cpi!(EntrantClosed {
+            raffle: raffle.key(),
+            entrant: entrant.key(),
+            user: entrant.user,
+            tickets: entrant.tickets,
+            rewards: entrant.rewards,
+            refund_amount,
+            reward_amount,
+            swept: raffle.swept,
+        });
+
+        entrant.close(user.clone())?;
+    }
+
+    Ok(())
+}
+
+
+/*
+ * Create the user's associated token account unless it already exists
+ */
+
+fn create_token_account_if_needed<'info>(
+    token_account: &AccountInfo<'info>,
+    user: &AccountInfo<'info>,
+    mint: &Account<'info, Mint>,
+    payer: &Signer<'info>,
+    token_program: &Program<'info, Token2022>,
+    associated_token_program: &Program<'info, AssociatedToken>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+
+    let expected_address = get_associated_token_address_with_program_id(&user.key(), &mint.key(), &token_program.key());
+
+    require!(
+        token_account.key() == expected_address,
+        RaffleError::InvalidSettlementAccounts
+    );
+
+    if token_account.data_is_empty() {
+        let cpi_accounts = associated_token::Create {
+            payer: payer.to_account_info(),
+            associated_token: token_account.clone(),
+            authority: user.clone(),
+            mint: mint.to_account_info(),
+            system_program: system_program.to_account_info(),
+            token_program: token_program.to_account_info(),
+        };
+
+        let cpi_program = associated_token_program.to_account_info();
+        associated_token::create(CpiContext::new(cpi_program, cpi_accounts))?;
+    }
+
+    Ok(())
+}
+
+
+/*
+ * Pay out the entrant's refund and reward, returns the refunded and minted amounts and whether
+ * the reward is still pending
+ *
+ * Reward minting is blocked while paused. A winner with losing tickets is refunded anyway and
+ * keeps only its winning tickets, the entrant stays open until the reward can be minted.
+ */
+
+fn pay_out<'info>(
+    raffle: &mut Account<'info, Raffle>,
+    entrant: &mut Entrant,
+    admin_settings: &AdminSettings,
+    proceeds: &Account<'info, TokenAccount>,
+    currency: &Account<'info, Mint>,
+    user_proceeds: AccountInfo<'info>,
+    reward: &Account<'info, Mint>,
+    user_reward: AccountInfo<'info>,
+    token_program: &Program<'info, Token2022>,
+) -> Result<(u64, u64, bool)> {
+
+    // Unclaimed refunds and rewards were swept after the claim deadline, only the rent is returned
+    if raffle.swept {
+        return Ok((0, 0, false));
+    }
+
     let refundable_tickets = entrant.get_refundable_tickets()?;
+    if entrant.rewards > 0 && refundable_tickets == 0 {
+        admin_settings.assert_not_paused()?;
+        raffle.assert_not_paused()?;
+    }
+
     let refundable_amount = raffle.get_refunable_proceeds(refundable_tickets)?;
+    raffle.refund_tickets(refundable_tickets)?;
     if refundable_amount > 0 {
         let cpi_transfer_accounts = TransferChecked {
-            from: ctx.accounts.proceeds.to_account_info(),
-            mint: ctx.accounts.currency.to_account_info(),
-            to: ctx.accounts.user_proceeds.to_account_info(),
+            from: proceeds.to_account_info(),
+            mint: currency.to_account_info(),
+            to: user_proceeds,
             authority: raffle.to_account_info(),
         };
     
-        let cpi_transfer_program = ctx.accounts.token_program.to_account_info();
+        let cpi_transfer_program = token_program.to_account_info();
         token_2022::transfer_checked(
             CpiContext::new_with_signer(
                 cpi_transfer_program,
@@ -154,17 +511,25 @@ pub fn close(ctx: Context<CloseEntrant>) -> Result<()> {
         )?;
     }
 
+    if entrant.rewards > 0 && (admin_settings.paused || raffle.paused) {
+        entrant.tickets = entrant.rewards;
+
+        return Ok((refundable_amount, 0, true));
+    }
+
+    if entrant.rewards > 0 {
+        raffle.claim_rewards(entrant.rewards)?;
+    }
+
     let reward_amount = raffle.get_reward_amount(entrant.rewards)?;
     if reward_amount > 0 {
-        raffle.claim_rewards(entrant.rewards)?;
-    
         let cpi_mint_accounts = MintTo {
-            mint: ctx.accounts.reward.to_account_info(),
-            to: ctx.accounts.user_reward.to_account_info(),
+            mint: reward.to_account_info(),
+            to: user_reward,
             authority: raffle.to_account_info(),
         };
 
-        let cpi_mint_program = ctx.accounts.token_program.to_account_info();
+        let cpi_mint_program = token_program.to_account_info();
         token_2022::mint_to(
             CpiContext::new_with_signer(
                 cpi_mint_program,
@@ -175,7 +540,58 @@ pub fn close(ctx: Context<CloseEntrant>) -> Result<()> {
         )?;
     }
 
-    Ok(())
+    Ok((refundable_amount, reward_amount, false))
 }
 
 
+
+/*
+ * Agree or disagree to the raffle's end timestamp being shortened
+ */
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetShortenOptIn<'info> {
+
+    // Raffle account
+    #[account(mut)]
+    pub raffle: Account<'info, Raffle>,
+
+    // Entrant account
+    #[account(
+        mut,
+        seeds = [b"entrant".as_ref(), raffle.key().as_ref(), user.key().as_ref()],
+        bump,
+    )]
+    pub entrant: Account<'info, Entrant>,
+
+    // User
+    pub user: Signer<'info>,
+}
+
+pub fn set_shorten_opt_in(ctx: Context<SetShortenOptIn>, opt_in: bool) -> Result<()> {
+
+    let raffle = &mut ctx.accounts.raffle;
+    let entrant = &mut ctx.accounts.entrant;
+
+    require!(
+        entrant.tickets > 0,
+        RaffleError::EntrantHasNoTickets
+    );
+
+    if opt_in && !entrant.shorten_opt_in {
+        raffle.shorten_opt_ins = raffle.shorten_opt_ins.checked_add(1).ok_or(RaffleError::InvalidCalculation)?;
+    } else if !opt_in && entrant.shorten_opt_in {
+        raffle.shorten_opt_ins = raffle.shorten_opt_ins.checked_sub(1).ok_or(RaffleError::InvalidCalculation)?;
+    }
+
+    entrant.shorten_opt_in = opt_in;
+
+    emit_cpi!(ShortenOptInChanged {
+        raffle: raffle.key(),
+        user: entrant.user,
+        opt_in,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,224 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+use anchor_lang::{system_program, Discriminator};
+use anchor_spl::token::TokenAccount;
+
+use crate::errors::*;
+use crate::events::*;
+use crate::states::*;
+
+
+/*
+ * Migrate the AdminSettings account to the current layout
+ */
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MigrateAdminSettings<'info> {
+
+    /// CHECK: Legacy AdminSettings account, validated and rewritten by the handler
+    #[account(
+        mut,
+        seeds = [b"admin".as_ref()],
+        bump,
+    )]
+    pub admin_settings: UncheckedAccount<'info>,
+
+    // ShitcoinRaffle program data
+    #[account(
+        seeds = [crate::ID.as_ref()],
+        bump,
+        seeds::program = bpf_loader_upgradeable::ID,
+        constraint = program_data.upgrade_authority_address == Some(authority.key())
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    // Authority for migrating the AdminSettings account -> upgrade authority of the ShitcoinRaffle program
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn migrate_admin_settings(ctx: Context<MigrateAdminSettings>) -> Result<()> {
+
+    migrate::<AdminSettingsV0, AdminSettings>(
+        &ctx.accounts.admin_settings,
+        &ctx.accounts.authority,
+        &ctx.accounts.system_program,
+        AdminSettings::SPACE,
+        |legacy| Ok(legacy.into()),
+    )?;
+
+    emit_cpi!(AccountMigrated {
+        account: ctx.accounts.admin_settings.key(),
+        version: AdminSettings::VERSION,
+    });
+
+    Ok(())
+}
+
+
+/*
+ * Migrate a Raffle account to the current layout
+ */
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MigrateRaffle<'info> {
+
+    // AdminSettings account
+    #[account(
+        seeds = [b"admin".as_ref()],
+        bump
+    )]
+    pub admin_settings: Account<'info, AdminSettings>,
+
+    /// CHECK: Legacy Raffle account, validated and rewritten by the handler
+    #[account(mut)]
+    pub raffle: UncheckedAccount<'info>,
+
+    // Proceeds token account, its balance tells the refunds paid before the migration
+    #[account(
+        seeds = [b"proceeds".as_ref(), raffle.key().as_ref()],
+        bump,
+        token::authority = raffle,
+    )]
+    pub proceeds: Account<'info, TokenAccount>,
+
+    // Admin account
+    #[account(mut, constraint = admin_settings.admin == authority.key())]
+    pub authority: Signer<'info>,
+
+    // System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn migrate_raffle(ctx: Context<MigrateRaffle>) -> Result<()> {
+
+    let admin = ctx.accounts.admin_settings.admin;
+    let proceeds = ctx.accounts.proceeds.amount;
+    let now = Clock::get()?.unix_timestamp;
+
+    migrate::<RaffleV0, Raffle>(
+        &ctx.accounts.raffle,
+        &ctx.accounts.authority,
+        &ctx.accounts.system_program,
+        Raffle::SPACE,
+        |legacy| legacy.migrate(admin, proceeds, now),
+    )?;
+
+    emit_cpi!(AccountMigrated {
+        account: ctx.accounts.raffle.key(),
+        version: Raffle::VERSION,
+    });
+
+    Ok(())
+}
+
+
+/*
+ * Migrate an Entrant account to the current layout
+ */
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MigrateEntrant<'info> {
+
+    // AdminSettings account
+    #[account(
+        seeds = [b"admin".as_ref()],
+        bump
+    )]
+    pub admin_settings: Account<'info, AdminSettings>,
+
+    /// CHECK: Legacy Entrant account, validated and rewritten by the handler
+    #[account(mut)]
+    pub entrant: UncheckedAccount<'info>,
+
+    // Admin account
+    #[account(mut, constraint = admin_settings.admin == authority.key())]
+    pub authority: Signer<'info>,
+
+    // System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn migrate_entrant(ctx: Context<MigrateEntrant>) -> Result<()> {
+
+    migrate::<EntrantV0, Entrant>(
+        &ctx.accounts.entrant,
+        &ctx.accounts.authority,
+        &ctx.accounts.system_program,
+        Entrant::SPACE,
+        |legacy| Ok(legacy.into()),
+    )?;
+
+    emit_cpi!(AccountMigrated {
+        account: ctx.accounts.entrant.key(),
+        version: Entrant::VERSION,
+    });
+
+    Ok(())
+}
+
+
+/*
+ * Reallocate a legacy account to the current layout and fill the defaults
+ */
+
+fn migrate<'info, Legacy, Current>(
+    account: &UncheckedAccount<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    space: usize,
+    upgrade: impl FnOnce(Legacy) -> Result<Current>,
+) -> Result<()>
+where
+    Legacy: AnchorDeserialize,
+    Current: AccountSerialize + Discriminator,
+{
+    require_keys_eq!(
+        *account.owner,
+        crate::ID,
+        RaffleError::InvalidMigrationAccount
+    );
+
+    let legacy = {
+        let data = account.try_borrow_data()?;
+
+        require!(
+            data.len() >= 8 && data[..8] == Current::discriminator(),
+            RaffleError::InvalidMigrationAccount
+        );
+
+        require!(
+            data.len() < space,
+            RaffleError::AccountAlreadyMigrated
+        );
+
+        Legacy::deserialize(&mut &data[8..])?
+    };
+
+    let current = upgrade(legacy)?;
+
+    let rent = Rent::get()?;
+    let required_lamports = rent.minimum_balance(space).saturating_sub(account.lamports());
+    if required_lamports > 0 {
+        let cpi_accounts = system_program::Transfer {
+            from: payer.to_account_info(),
+            to: account.to_account_info(),
+        };
+
+        let cpi_program = system_program.to_account_info();
+        system_program::transfer(CpiContext::new(cpi_program, cpi_accounts), required_lamports)?;
+    }
+
+    account.realloc(space, true)?;
+
+    let mut data = account.try_borrow_mut_data()?;
+    current.try_serialize(&mut &mut data[..])?;
+
+    Ok(())
+}
@@ -1,7 +1,11 @@
 pub mod config;
 pub mod raffle;
 pub mod entrant;
+pub mod referrer;
+pub mod migrate;
 
 pub use config::*;
 pub use raffle::*;
 pub use entrant::*;
+pub use referrer::*;
+pub use migrate::*;
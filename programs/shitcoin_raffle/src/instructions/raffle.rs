@@ -1,14 +1,18 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program;
 // use anchor_spl::{
 //     token::{self, Mint, Token, TokenAccount},
 // };
 use anchor_spl::{
     token::{Mint, TokenAccount},
     token_2022::{self, Token2022},
+    associated_token::AssociatedToken,
 };
 
 use crate::errors::*;
+use crate::events::*;
 use crate::states::*;
+use crate::validation::RaffleParams;
 
 
 
@@ -16,6 +20,7 @@ use crate::states::*;
  * Initialize a new raffle
  */
 
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(seed: Pubkey)]
 pub struct InitRaffle<'info> {
@@ -29,8 +34,8 @@ pub struct InitRaffle<'info> {
         init, 
         seeds = [b"raffle".as_ref(), seed.as_ref()], 
         bump, 
-        payer = authority, 
-        space = 8 + std::mem::size_of::<Raffle>(),
+        payer = creator, 
+        space = Raffle::SPACE,
     )]
     pub raffle: Account<'info, Raffle>,    
 
@@ -39,7 +44,7 @@ pub struct InitRaffle<'info> {
         init,
         seeds = [b"proceeds".as_ref(), raffle.key().as_ref()],
         bump,
-        payer = authority,
+        payer = creator,
         token::mint = currency,
         token::authority = raffle,
         token::token_program = token_program,
@@ -59,9 +64,13 @@ pub struct InitRaffle<'info> {
     )]
     pub reward: Account<'info, Mint>,
     
-    // Admin account
-    #[account(mut, constraint = admin_settings.admin == authority.key())]
-    pub authority: Signer<'info>,
+    // Treasury account, receives the creation fee
+    #[account(mut, address = admin_settings.get_treasury())]
+    pub treasury: SystemAccount<'info>,
+    
+    // Creator account
+    #[account(mut)]
+    pub creator: Signer<'info>,
 
     // Token program
     pub token_program: Program<'info, Token2022>,
@@ -81,53 +90,201 @@ pub fn initialize(
     fee: Option<u64>,
     tickets: Option<u64>,
     limit: Option<u64>,
+    referral_share: Option<u16>,
+    extension_window: Option<i64>,
+    extension_duration: Option<i64>,
+    max_auto_extension: Option<i64>,
+    claim_period: Option<i64>,
 ) -> Result<()> {
-        
-    let clock = Clock::get()?;
-    
-    require!(
-        price > fee.unwrap_or(0),
-        RaffleError::FeeGreaterThanPrice
-    );
 
-    require!(
-        rewards_num < tickets.unwrap_or(u64::MAX),
-        RaffleError::RewardsNumGreaterThanTickets
-    );
+    let params = RaffleParams {
+        price,
+        rewards_num,
+        rewards_amount,
+        start_timestamp,
+        end_timestamp,
+        fee,
+        tickets,
+        limit,
+        referral_share,
+        extension_window,
+        extension_duration,
+        max_auto_extension,
+        claim_period,
+    };
+    params.validate(Clock::get()?.unix_timestamp)?;
+        
+    let admin_settings = &ctx.accounts.admin_settings;
+    let creation_fee = if admin_settings.has_role(Role::Creator, &ctx.accounts.creator.key()) {
+        0
+    } else {
+        admin_settings.creation_fee
+    };
 
-    require!(
-        limit.unwrap_or(1) > 0,
-        RaffleError::LimitLessThanOne
-    );
+    if creation_fee > 0 {
+        let cpi_accounts = system_program::Transfer {
+            from: ctx.accounts.creator.to_account_info(),
+            to: ctx.accounts.treasury.to_account_info(),
+        };
 
-    require!(
-        start_timestamp < end_timestamp,
-        RaffleError::StartAfterEndTimestamp
-    );
-    
-    require!(
-        clock.unix_timestamp < end_timestamp,
-        RaffleError::EndTimestampAlreadyPassed
-    );
+        let cpi_program = ctx.accounts.system_program.to_account_info();
+        system_program::transfer(CpiContext::new(cpi_program, cpi_accounts), creation_fee)?;
+    }
     
     let raffle = &mut ctx.accounts.raffle;
+    raffle.version = Raffle::VERSION;
     raffle.bump = ctx.bumps["raffle"];
     raffle.seed = seed;
-    raffle.price = price;
-    raffle.fee = fee.unwrap_or(0);
+    raffle.creator = ctx.accounts.creator.key();
     raffle.currency = ctx.accounts.currency.key();
-    raffle.rewards_num = rewards_num;
-    raffle.rewards_amount = rewards_amount;
     raffle.reward = ctx.accounts.reward.key();
-    raffle.start_timestamp = start_timestamp;
-    raffle.end_timestamp = end_timestamp;
-    raffle.tickets = tickets;
-    raffle.limit = limit;
+    raffle.set_params(&params);
     raffle.tickets_sold = 0;
+    raffle.entrants = 0;
+    raffle.shorten_opt_ins = 0;
     raffle.rewards_awarded = 0;
     raffle.rewards_claimed = 0;
+    raffle.rewards_forfeited = 0;
+    raffle.tickets_refunded = 0;
+    raffle.claim_deadline = None;
+    raffle.swept = false;
     raffle.admin_claimed = false;
+    raffle.status = RaffleStatus::Pending;
+    raffle.referral_accrued = 0;
+    raffle.referral_claimed = 0;
+    raffle.paused = false;
+    raffle.paused_at = None;
+    raffle.legacy = false;
+
+    let old_status = raffle.status;
+    raffle.advance()?;
     
+    emit_cpi!(RaffleCreated {
+        raffle: raffle.key(),
+        seed,
+        creator: raffle.creator,
+        currency: raffle.currency,
+        reward: raffle.reward,
+        price,
+        fee: raffle.fee,
+        rewards_num,
+        rewards_amount,
+        start_timestamp,
+        end_timestamp,
+        tickets,
+        limit,
+        referral_share: raffle.referral_share,
+        extension_window: raffle.extension_window,
+        extension_duration: raffle.extension_duration,
+        max_auto_extension: raffle.max_auto_extension,
+        claim_period,
+        creation_fee,
+    });
+
+    if raffle.status != old_status {
+        emit_cpi!(StatusChanged {
+            raffle: raffle.key(),
+            old_status,
+            new_status: raffle.status,
+        });
+    }
+
+    Ok(())
+}
+
+
+/*
+ * Update raffle parameters before the raffle starts
+ */
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateRaffle<'info> {
+
+    // AdminSettings account
+    #[account(
+        seeds = [b"admin".as_ref()], 
+        bump
+    )]
+    pub admin_settings: Account<'info, AdminSettings>,
+
+    // Raffle account
+    #[account(mut)]
+    pub raffle: Account<'info, Raffle>,
+
+    // Raffle creator or creator role account
+    #[account(constraint = raffle.creator == authority.key() || admin_settings.has_role(Role::Creator, &authority.key()))]
+    pub authority: Signer<'info>,
+}
+
+pub fn update(
+    ctx: Context<UpdateRaffle>,
+    price: u64,
+    rewards_num: u64,
+    rewards_amount: u64,
+    start_timestamp: i64,
+    end_timestamp: i64,
+    fee: Option<u64>,
+    tickets: Option<u64>,
+    limit: Option<u64>,
+    referral_share: Option<u16>,
+    extension_window: Option<i64>,
+    extension_duration: Option<i64>,
+    max_auto_extension: Option<i64>,
+    claim_period: Option<i64>,
+) -> Result<()> {
+
+    let params = RaffleParams {
+        price,
+        rewards_num,
+        rewards_amount,
+        start_timestamp,
+        end_timestamp,
+        fee,
+        tickets,
+        limit,
+        referral_share,
+        extension_window,
+        extension_duration,
+        max_auto_extension,
+        claim_period,
+    };
+
+    let raffle = &mut ctx.accounts.raffle;
+    let old_status = raffle.status;
+
+    raffle.advance()?;
+    raffle.assert_not_started()?;
+
+    params.validate(Clock::get()?.unix_timestamp)?;
+    raffle.set_params(&params);
+    raffle.advance()?;
+
+    emit_cpi!(RaffleUpdated {
+        raffle: raffle.key(),
+        price,
+        fee: raffle.fee,
+        rewards_num,
+        rewards_amount,
+        start_timestamp,
+        end_timestamp,
+        tickets,
+        limit,
+        referral_share: raffle.referral_share,
+        extension_window: raffle.extension_window,
+        extension_duration: raffle.extension_duration,
+        max_auto_extension: raffle.max_auto_extension,
+        claim_period,
+    });
+
+    if raffle.status != old_status {
+        emit_cpi!(StatusChanged {
+            raffle: raffle.key(),
+            old_status,
+            new_status: raffle.status,
+        });
+    }
+
     Ok(())
 }
 
@@ -136,9 +293,17 @@ pub fn initialize(
  * Buy tickets
  */
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct BuyTickets<'info> {
 
+    // AdminSettings account
+    #[account(
+        seeds = [b"admin".as_ref()], 
+        bump
+    )]
+    pub admin_settings: Account<'info, AdminSettings>,
+
     // Raffle account
     #[account(mut)]
     pub raffle: Account<'info, Raffle>,
@@ -151,6 +316,13 @@ pub struct BuyTickets<'info> {
     )]
     pub entrant: Account<'info, Entrant>,
 
+    // Optional referrer account
+    #[account(
+        mut,
+        constraint = referrer.raffle == raffle.key(),
+    )]
+    pub referrer: Option<Account<'info, Referrer>>,
+
     // Proceeds token account
     #[account(
         mut,
@@ -189,6 +361,10 @@ pub fn buy_tickets(
     let entrant = &mut ctx.accounts.entrant;
     let currency = &ctx.accounts.currency;
     
+    ctx.accounts.admin_settings.assert_not_paused()?;
+    raffle.assert_not_paused()?;
+    let old_status = raffle.status;
+    raffle.advance()?;
     raffle.assert_active()?;
     
     let total_price = raffle.price.checked_mul(amount).ok_or(RaffleError::InvalidCalculation)?;
@@ -222,9 +398,61 @@ pub fn buy_tickets(
     let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
     token_2022::transfer_checked(cpi_ctx, total_price, currency.decimals)?;
     
+    if entrant.tickets == 0 {
+        raffle.entrants = raffle.entrants.checked_add(1).ok_or(RaffleError::InvalidCalculation)?;
+    }
+
     raffle.tickets_sold = total_tickets;
     entrant.tickets = entrant_tickets;
-    
+
+    let old_end_timestamp = raffle.end_timestamp;
+    raffle.auto_extend()?;
+
+    if raffle.end_timestamp != old_end_timestamp {
+        emit_cpi!(EndTimestampChanged {
+            raffle: raffle.key(),
+            old_end_timestamp,
+            new_end_timestamp: raffle.end_timestamp,
+        });
+    }
+
+    raffle.advance()?;
+
+    let referral_amount = if let Some(referrer) = &mut ctx.accounts.referrer {
+        require!(
+            referrer.user != ctx.accounts.user.key(),
+            RaffleError::SelfReferral
+        );
+
+        let referral_amount = raffle.get_referral_amount(amount)?;
+        raffle.accrue_referral(referral_amount)?;
+        
+        referrer.tickets = referrer.tickets.checked_add(amount).ok_or(RaffleError::InvalidCalculation)?;
+        referrer.accrued = referrer.accrued.checked_add(referral_amount).ok_or(RaffleError::InvalidCalculation)?;
+
+        referral_amount
+    } else {
+        0
+    };
+
+    emit_cpi!(TicketsPurchased {
+        raffle: raffle.key(),
+        user: ctx.accounts.user.key(),
+        amount,
+        total_price,
+        tickets_sold: raffle.tickets_sold,
+        referrer: ctx.accounts.referrer.as_ref().map(|referrer| referrer.key()),
+        referral_amount,
+    });
+
+    if raffle.status != old_status {
+        emit_cpi!(StatusChanged {
+            raffle: raffle.key(),
+            old_status,
+            new_status: raffle.status,
+        });
+    }
+
     Ok(())
 }
 
@@ -233,6 +461,7 @@ pub fn buy_tickets(
  * Set rewards
  */
 
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(_user: Pubkey)]
 pub struct SetRewards<'info> {
@@ -256,8 +485,8 @@ pub struct SetRewards<'info> {
     )]
     pub entrant: Account<'info, Entrant>,
 
-    // Admin account
-    #[account(constraint = admin_settings.admin == authority.key())]
+    // Raffle creator or drawer account
+    #[account(constraint = raffle.creator == authority.key() || admin_settings.has_role(Role::Drawer, &authority.key()))]
     pub authority: Signer<'info>,
 }
 
@@ -269,25 +498,38 @@ pub fn set_rewards(
     
     let raffle = &mut ctx.accounts.raffle;
     let entrant = &mut ctx.accounts.entrant;
+    let old_status = raffle.status;
     
+    raffle.advance()?;
     raffle.assert_ended()?;
+    raffle.assert_status(RaffleStatus::Ended)?;
     entrant.assert_not_awarded()?;
 
-    let rewards_awarded = raffle.rewards_awarded.checked_add(amount).ok_or(RaffleError::InvalidCalculation)?;
-    
     require!(
         amount <= entrant.tickets,
         RaffleError::RewardsNumGreaterThanTicketsBought
     );
     
-    require!(
-        rewards_awarded <= raffle.rewards_amount,
-        RaffleError::RewardsAmountGreaterThanTotal
-    );
-    
-    raffle.rewards_awarded = rewards_awarded;
+    raffle.award_rewards(amount)?;
     entrant.rewards = amount;
+
+    raffle.advance()?;
     
+    emit_cpi!(RewardSet {
+        raffle: raffle.key(),
+        user: entrant.user,
+        amount,
+        rewards_awarded: raffle.rewards_awarded,
+    });
+
+    if raffle.status != old_status {
+        emit_cpi!(StatusChanged {
+            raffle: raffle.key(),
+            old_status,
+            new_status: raffle.status,
+        });
+    }
+
     Ok(())
 }
 
@@ -296,6 +538,7 @@ pub fn set_rewards(
  * Claim proceeds
  */
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct ClaimProceeds<'info> {
 
@@ -320,21 +563,25 @@ pub struct ClaimProceeds<'info> {
     )]
     pub proceeds: Account<'info, TokenAccount>,
 
-    // Admin's proceeds token account
+    // Creator's proceeds token account
     #[account(
         mut,
         associated_token::mint = currency,
-        associated_token::authority = authority,
+        associated_token::authority = creator,
     )]
-    pub admin_proceeds: Account<'info, TokenAccount>,
+    pub creator_proceeds: Account<'info, TokenAccount>,
 
     // Proceeds mint
     pub currency: Account<'info, Mint>,
 
-    // Admin account
+    // Creator account
+    #[account(address = raffle.creator)]
+    pub creator: SystemAccount<'info>,
+
+    // Raffle creator or treasurer account
     #[account(
         mut,
-        constraint = admin_settings.admin == authority.key()
+        constraint = raffle.creator == authority.key() || admin_settings.has_role(Role::Treasurer, &authority.key())
     )]
     pub authority: Signer<'info>,
 
@@ -346,7 +593,9 @@ pub fn claim_proceeds(ctx: Context<ClaimProceeds>,) -> Result<()> {
     
     let raffle = &mut ctx.accounts.raffle;
     let currency = &ctx.accounts.currency;
+    let old_status = raffle.status;
     
+    raffle.advance()?;
     raffle.assert_claimable()?;
     
     let authority_proceeds = raffle.get_authority_proceeds()?;
@@ -354,7 +603,7 @@ pub fn claim_proceeds(ctx: Context<ClaimProceeds>,) -> Result<()> {
         let cpi_accounts = token_2022::TransferChecked {
             from: ctx.accounts.proceeds.to_account_info(),
             mint: ctx.accounts.currency.to_account_info(),
-            to: ctx.accounts.admin_proceeds.to_account_info(),
+            to: ctx.accounts.creator_proceeds.to_account_info(),
             authority: raffle.to_account_info(),
         };
 
@@ -371,7 +620,22 @@ pub fn claim_proceeds(ctx: Context<ClaimProceeds>,) -> Result<()> {
     }
 
     raffle.admin_claimed = true;
+    raffle.transition(RaffleStatus::Settled)?;
     
+    emit_cpi!(ProceedsClaimed {
+        raffle: raffle.key(),
+        creator: raffle.creator,
+        amount: authority_proceeds,
+    });
+
+    if raffle.status != old_status {
+        emit_cpi!(StatusChanged {
+            raffle: raffle.key(),
+            old_status,
+            new_status: raffle.status,
+        });
+    }
+
     Ok(())
 }
 
@@ -380,6 +644,7 @@ pub fn claim_proceeds(ctx: Context<ClaimProceeds>,) -> Result<()> {
  * Close raffle
  */
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct CloseRaffle<'info> {
     
@@ -393,7 +658,7 @@ pub struct CloseRaffle<'info> {
     // Raffle account
     #[account(
         mut,
-        close = authority
+        close = creator
     )]
     pub raffle: Account<'info, Raffle>,
 
@@ -410,10 +675,14 @@ pub struct CloseRaffle<'info> {
     // Proceeds mint
     pub currency: Account<'info, Mint>,
 
-    // Admin account
+    // Creator account, receives the rent
+    #[account(mut, address = raffle.creator)]
+    pub creator: SystemAccount<'info>,
+
+    // Raffle creator or treasurer account
     #[account(
         mut,
-        constraint = admin_settings.admin == authority.key()
+        constraint = raffle.creator == authority.key() || admin_settings.has_role(Role::Treasurer, &authority.key())
     )]
     pub authority: Signer<'info>,
 
@@ -423,13 +692,15 @@ pub struct CloseRaffle<'info> {
 
 
 pub fn close(ctx: Context<CloseRaffle>) -> Result<()> {
-    let raffle = &ctx.accounts.raffle;
+    let raffle = &mut ctx.accounts.raffle;
+    let old_status = raffle.status;
 
+    raffle.advance()?;
     raffle.assert_closeable()?;
     
     let cpi_accounts = token_2022::CloseAccount {
         account: ctx.accounts.proceeds.to_account_info(),
-        destination: ctx.accounts.authority.to_account_info(),
+        destination: ctx.accounts.creator.to_account_info(),
         authority: raffle.to_account_info(),
     };
 
@@ -442,8 +713,477 @@ pub fn close(ctx: Context<CloseRaffle>) -> Result<()> {
         )
     )?;
 
+    if raffle.status != old_status {
+        emit_cpi!(StatusChanged {
+            raffle: raffle.key(),
+            old_status,
+            new_status: raffle.status,
+        });
+    }
+
+    emit_cpi!(RaffleClosed {
+        raffle: raffle.key(),
+        creator: raffle.creator,
+    });
+
+    Ok(())
+}
+
+
+
+/*
+ * Extend or shorten a live raffle
+ */
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetEndTimestamp<'info> {
+
+    // AdminSettings account
+    #[account(
+        seeds = [b"admin".as_ref()], 
+        bump
+    )]
+    pub admin_settings: Account<'info, AdminSettings>,
+
+    // Raffle account
+    #[account(mut)]
+    pub raffle: Account<'info, Raffle>,
+
+    // Raffle creator or creator role account
+    #[account(constraint = raffle.creator == authority.key() || admin_settings.has_role(Role::Creator, &authority.key()))]
+    pub authority: Signer<'info>,
+}
+
+pub fn set_end_timestamp(
+    ctx: Context<SetEndTimestamp>,
+    end_timestamp: i64,
+) -> Result<()> {
+
+    let admin_settings = &ctx.accounts.admin_settings;
+    let raffle = &mut ctx.accounts.raffle;
+    let old_end_timestamp = raffle.end_timestamp;
+    let old_status = raffle.status;
+
+    raffle.advance()?;
+    raffle.assert_active()?;
+    raffle.set_end_timestamp(end_timestamp, admin_settings.max_extension)?;
+
+    emit_cpi!(EndTimestampChanged {
+        raffle: raffle.key(),
+        old_end_timestamp,
+        new_end_timestamp: end_timestamp,
+    });
+
+    if raffle.status != old_status {
+        emit_cpi!(StatusChanged {
+            raffle: raffle.key(),
+            old_status,
+            new_status: raffle.status,
+        });
+    }
+
+    Ok(())
+}
+
+
+/*
+ * Pause or unpause raffle
+ */
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetRafflePaused<'info> {
+
+    // AdminSettings account
+    #[account(
+        seeds = [b"admin".as_ref()], 
+        bump
+    )]
+    pub admin_settings: Account<'info, AdminSettings>,
+
+    // Raffle account
+    #[account(mut)]
+    pub raffle: Account<'info, Raffle>,
+
+    // Pauser account
+    #[account(constraint = admin_settings.has_role(Role::Pauser, &authority.key()))]
+    pub authority: Signer<'info>,
+}
+
+pub fn set_paused(
+    ctx: Context<SetRafflePaused>,
+    paused: bool,
+    extend: bool,
+) -> Result<()> {
+
+    let raffle = &mut ctx.accounts.raffle;
+    let old_end_timestamp = raffle.end_timestamp;
+    let old_status = raffle.status;
+
+    if paused {
+        // Status is frozen while paused, a raffle past its end has to be ended first
+        raffle.advance()?;
+        raffle.pause()?;
+    } else {
+        raffle.unpause(extend, ctx.accounts.admin_settings.max_extension)?;
+    }
+
+    if raffle.end_timestamp != old_end_timestamp {
+        emit_cpi!(EndTimestampChanged {
+            raffle: raffle.key(),
+            old_end_timestamp,
+            new_end_timestamp: raffle.end_timestamp,
+        });
+    }
+
+    if raffle.status != old_status {
+        emit_cpi!(StatusChanged {
+            raffle: raffle.key(),
+            old_status,
+            new_status: raffle.status,
+        });
+    }
+
+    emit_cpi!(PauseChanged {
+        raffle: Some(raffle.key()),
+        paused,
+    });
+
+    Ok(())
+}
+
+
+
+/*
+ * Cancel raffle before any ticket is sold
+ */
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelRaffle<'info> {
+
+    // AdminSettings account
+    #[account(
+        seeds = [b"admin".as_ref()], 
+        bump
+    )]
+    pub admin_settings: Account<'info, AdminSettings>,
+
+    // Raffle account
+    #[account(mut)]
+    pub raffle: Account<'info, Raffle>,
+
+    // Raffle creator or creator role account
+    #[account(constraint = raffle.creator == authority.key() || admin_settings.has_role(Role::Creator, &authority.key()))]
+    pub authority: Signer<'info>,
+}
+
+pub fn cancel(ctx: Context<CancelRaffle>) -> Result<()> {
+
+    let raffle = &mut ctx.accounts.raffle;
+    let old_status = raffle.status;
+
+    raffle.advance()?;
+    raffle.cancel()?;
+
+    emit_cpi!(RaffleCancelled {
+        raffle: raffle.key(),
+    });
+
+    if raffle.status != old_status {
+        emit_cpi!(StatusChanged {
+            raffle: raffle.key(),
+            old_status,
+            new_status: raffle.status,
+        });
+    }
+
+    Ok(())
+}
+
+
+
+/*
+ * Advance the raffle status, callable by anyone
+ */
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CrankStatus<'info> {
+
+    // Raffle account
+    #[account(mut)]
+    pub raffle: Account<'info, Raffle>,
+}
+
+pub fn crank_status(ctx: Context<CrankStatus>) -> Result<()> {
+
+    let raffle = &mut ctx.accounts.raffle;
+    let old_status = raffle.status;
+
+    raffle.advance()?;
+
+    if raffle.status != old_status {
+        emit_cpi!(StatusChanged {
+            raffle: raffle.key(),
+            old_status,
+            new_status: raffle.status,
+        });
+    }
+
+    Ok(())
+}
+
+
+/*
+ * Check the proceeds balance covers the raffle's liabilities, callable by anyone
+ */
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct VerifySolvency<'info> {
+
+    // Raffle account
+    pub raffle: Account<'info, Raffle>,
+
+    // Proceeds token account
+    #[account(
+        seeds = [b"proceeds".as_ref(), raffle.key().as_ref()],
+        bump,
+        token::authority = raffle,
+    )]
+    pub proceeds: Account<'info, TokenAccount>,
+}
+
+pub fn verify_solvency(ctx: Context<VerifySolvency>) -> Result<()> {
+
+    let raffle = &ctx.accounts.raffle;
+    let liabilities = raffle.get_liabilities()?;
+    let balance = ctx.accounts.proceeds.amount;
+
+    require!(
+        balance >= liabilities,
+        RaffleError::VaultInsolvent
+    );
+
+    emit_cpi!(SolvencyVerified {
+        raffle: raffle.key(),
+        liabilities,
+        balance,
+    });
+
+    Ok(())
+}
+
+
+/*
+ * Sweep unclaimed refunds to the treasury after the claim deadline
+ */
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SweepUnclaimed<'info> {
+
+    // AdminSettings account
+    #[account(
+        seeds = [b"admin".as_ref()], 
+        bump
+    )]
+    pub admin_settings: Account<'info, AdminSettings>,
+
+    // Raffle account
+    #[account(mut)]
+    pub raffle: Account<'info, Raffle>,
+
+    // Proceeds token account
+    #[account(
+        mut,
+        seeds = [b"proceeds".as_ref(), raffle.key().as_ref()],
+        bump,
+        token::mint = currency,
+        token::authority = raffle,
+    )]
+    pub proceeds: Account<'info, TokenAccount>,
+
+    // Treasury's proceeds token account
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = currency,
+        associated_token::authority = treasury,
+    )]
+    pub treasury_proceeds: Account<'info, TokenAccount>,
+
+    // Proceeds mint
+    pub currency: Account<'info, Mint>,
+
+    // Treasury account
+    #[account(address = admin_settings.get_treasury())]
+    pub treasury: SystemAccount<'info>,
+
+    // Treasurer account
+    #[account(
+        mut,
+        constraint = admin_settings.has_role(Role::Treasurer, &authority.key())
+    )]
+    pub authority: Signer<'info>,
+
+    // Token program
+    pub token_program: Program<'info, Token2022>,
+
+    // Associated token program
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    // System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn sweep_unclaimed(ctx: Context<SweepUnclaimed>) -> Result<()> {
+
+    let raffle = &mut ctx.accounts.raffle;
+    let currency = &ctx.accounts.currency;
+    let old_status = raffle.status;
+
+    raffle.advance()?;
+    raffle.assert_sweepable()?;
+
+    let swept_amount = raffle.sweep()?;
+    if swept_amount > 0 {
+        let cpi_accounts = token_2022::TransferChecked {
+            from: ctx.accounts.proceeds.to_account_info(),
+            mint: ctx.accounts.currency.to_account_info(),
+            to: ctx.accounts.treasury_proceeds.to_account_info(),
+            authority: raffle.to_account_info(),
+        };
+
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token_2022::transfer_checked(
+            CpiContext::new_with_signer(
+                cpi_program,
+                cpi_accounts,
+                &[&[b"raffle".as_ref(), raffle.seed.as_ref(), &[raffle.bump]]]
+            ),
+            swept_amount,
+            currency.decimals
+        )?;
+    }
+
+    emit_cpi!(UnclaimedSwept {
+        raffle: raffle.key(),
+        treasury: ctx.accounts.treasury.key(),
+        amount: swept_amount,
+        rewards_forfeited: raffle.rewards_forfeited,
+    });
+
+    if raffle.status != old_status {
+        emit_cpi!(StatusChanged {
+            raffle: raffle.key(),
+            old_status,
+            new_status: raffle.status,
+        });
+    }
+
+    Ok(())
+}
+
+
+/*
+ * Sweep tokens sent to the raffle's token accounts beyond what it owes to the treasury
+ */
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SweepExcess<'info> {
+
+    // AdminSettings account
+    #[account(
+        seeds = [b"admin".as_ref()], 
+        bump
+    )]
+    pub admin_settings: Account<'info, AdminSettings>,
+
+    // Raffle account
+    pub raffle: Account<'info, Raffle>,
+
+    // Proceeds token account or any other token account owned by the raffle
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = raffle,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    // Treasury's token account
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = mint,
+        associated_token::authority = treasury,
+    )]
+    pub treasury_vault: Account<'info, TokenAccount>,
+
+    // Mint of the vault
+    pub mint: Account<'info, Mint>,
+
+    // Treasury account
+    #[account(address = admin_settings.get_treasury())]
+    pub treasury: SystemAccount<'info>,
+
+    // Treasurer account
+    #[account(
+        mut,
+        constraint = admin_settings.has_role(Role::Treasurer, &authority.key())
+    )]
+    pub authority: Signer<'info>,
+
+    // Token program
+    pub token_program: Program<'info, Token2022>,
+
+    // Associated token program
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    // System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn sweep_excess(ctx: Context<SweepExcess>) -> Result<()> {
+
+    let raffle = &ctx.accounts.raffle;
+    let vault = &ctx.accounts.vault;
+
+    // Only the proceeds account holds anything the raffle owes, other accounts are swept entirely
+    let (proceeds, _) = Pubkey::find_program_address(&[b"proceeds".as_ref(), raffle.key().as_ref()], ctx.program_id);
+    let liabilities = if vault.key() == proceeds { raffle.get_liabilities()? } else { 0 };
+    let excess_amount = vault.amount.checked_sub(liabilities).ok_or(RaffleError::VaultInsolvent)?;
+
+    if excess_amount > 0 {
+        let cpi_accounts = token_2022::TransferChecked {
+            from: vault.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.treasury_vault.to_account_info(),
+            authority: raffle.to_account_info(),
+        };
+
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token_2022::transfer_checked(
+            CpiContext::new_with_signer(
+                cpi_program,
+                cpi_accounts,
+                &[&[b"raffle".as_ref(), raffle.seed.as_ref(), &[raffle.bump]]]
+            ),
+            excess_amount,
+            ctx.accounts.mint.decimals
+        )?;
+    }
+
+    emit_cpi!(ExcessSwept {
+        raffle: raffle.key(),
+        vault: vault.key(),
+        mint: ctx.accounts.mint.key(),
+        treasury: ctx.accounts.treasury.key(),
+        amount: excess_amount,
+    });
+
     Ok(())
 }
-    
-    
-    
@@ -0,0 +1,174 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token::{Mint, TokenAccount},
+    token_2022::{self, Token2022, TransferChecked},
+};
+
+use crate::events::*;
+use crate::states::*;
+
+
+/*
+ * Initialize a new referrer account
+ */
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct InitReferrer<'info> {
+
+    // Raffle account
+    #[account(mut)]
+    pub raffle: Account<'info, Raffle>,
+
+    // Referrer account
+    #[account(
+        init,
+        seeds = [b"referrer".as_ref(), raffle.key().as_ref(), user.key().as_ref()],
+        bump,
+        payer = user,
+        space = Referrer::SPACE,
+    )]
+    pub referrer: Account<'info, Referrer>,
+
+    // User
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    // System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize(ctx: Context<InitReferrer>) -> Result<()> {
+
+    let raffle = &mut ctx.accounts.raffle;
+    let referrer = &mut ctx.accounts.referrer;
+    let old_status = raffle.status;
+
+    raffle.advance()?;
+    raffle.assert_active()?;
+
+    referrer.version = Referrer::VERSION;
+    referrer.bump = ctx.bumps["referrer"];
+    referrer.user = ctx.accounts.user.key();
+    referrer.raffle = ctx.accounts.raffle.key();
+    referrer.tickets = 0;
+    referrer.accrued = 0;
+
+    emit_cpi!(ReferrerCreated {
+        raffle: referrer.raffle,
+        referrer: referrer.key(),
+        user: referrer.user,
+    });
+
+    if raffle.status != old_status {
+        emit_cpi!(StatusChanged {
+            raffle: raffle.key(),
+            old_status,
+            new_status: raffle.status,
+        });
+    }
+
+    Ok(())
+}
+
+
+/*
+ * Claim referral rewards and close referrer
+ */
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseReferrer<'info> {
+
+    // Raffle account
+    #[account(mut)]
+    pub raffle: Account<'info, Raffle>,
+
+    // Referrer account
+    #[account(
+        mut,
+        seeds = [b"referrer".as_ref(), raffle.key().as_ref(), user.key().as_ref()],
+        bump,
+        close = user,
+    )]
+    pub referrer: Account<'info, Referrer>,
+
+    // Proceeds token account
+    #[account(
+        mut,
+        seeds = [b"proceeds".as_ref(), raffle.key().as_ref()],
+        bump,
+        token::mint = currency,
+        token::authority = raffle,
+    )]
+    pub proceeds: Account<'info, TokenAccount>,
+
+    // User's proceeds token account
+    #[account(
+        mut,
+        associated_token::mint = currency,
+        associated_token::authority = user,
+    )]
+    pub user_proceeds: Account<'info, TokenAccount>,
+
+    // Proceeds mint
+    pub currency: Account<'info, Mint>,
+
+    // User
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    // Token program
+    pub token_program: Program<'info, Token2022>,
+}
+
+pub fn close(ctx: Context<CloseReferrer>) -> Result<()> {
+
+    let raffle = &mut ctx.accounts.raffle;
+    let referrer = &ctx.accounts.referrer;
+    let currency = &ctx.accounts.currency;
+    let old_status = raffle.status;
+
+    raffle.advance()?;
+    raffle.assert_ended()?;
+
+    let paid_out = referrer.accrued > 0 && !raffle.swept;
+    if paid_out {
+        raffle.claim_referral(referrer.accrued)?;
+
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.proceeds.to_account_info(),
+            mint: ctx.accounts.currency.to_account_info(),
+            to: ctx.accounts.user_proceeds.to_account_info(),
+            authority: raffle.to_account_info(),
+        };
+
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token_2022::transfer_checked(
+            CpiContext::new_with_signer(
+                cpi_program,
+                cpi_accounts,
+                &[&[b"raffle".as_ref(), raffle.seed.as_ref(), &[raffle.bump]]]
+            ),
+            referrer.accrued,
+            currency.decimals
+        )?;
+    }
+
+    emit_cpi!(ReferrerClosed {
+        raffle: raffle.key(),
+        referrer: referrer.key(),
+        user: referrer.user,
+        amount: if paid_out { referrer.accrued } else { 0 },
+    });
+
+    if raffle.status != old_status {
+        emit_cpi!(StatusChanged {
+            raffle: raffle.key(),
+            old_status,
+            new_status: raffle.status,
+        });
+    }
+
+    Ok(())
+}
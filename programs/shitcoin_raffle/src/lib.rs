@@ -1,9 +1,12 @@
 pub mod errors;
+pub mod events;
 pub mod states;
 pub mod instructions;
+pub mod validation;
 
 use anchor_lang::prelude::*;
 use instructions::*;
+use states::Role;
 
 declare_id!("DTXiWKJEs8DKd1K1Ex4TpyMNSyUdtxmoe7JjXM2gBzf5");
 
@@ -18,11 +21,52 @@ pub mod shitcoin_raffle {
         Ok(())
     }
 
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, admin: Pubkey) -> Result<()> {
+        instructions::config::propose_admin(ctx, admin)?;
+        Ok(())
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        instructions::config::accept_admin(ctx)?;
+        Ok(())
+    }
+
+    pub fn cancel_admin(ctx: Context<CancelAdmin>) -> Result<()> {
+        instructions::config::cancel_admin(ctx)?;
+        Ok(())
+    }
+
+    // Deprecated, use propose_admin and accept_admin
     pub fn set_admin(ctx: Context<SetAdmin>, admin: Pubkey) -> Result<()> {
         instructions::config::set_admin(ctx, admin)?;
         Ok(())
     }
 
+    pub fn set_creation_fee(ctx: Context<SetCreationFee>, creation_fee: u64) -> Result<()> {
+        instructions::config::set_creation_fee(ctx, creation_fee)?;
+        Ok(())
+    }
+
+    pub fn set_max_extension(ctx: Context<SetMaxExtension>, max_extension: i64) -> Result<()> {
+        instructions::config::set_max_extension(ctx, max_extension)?;
+        Ok(())
+    }
+
+    pub fn grant_role(ctx: Context<GrantRole>, role: Role, key: Pubkey) -> Result<()> {
+        instructions::config::grant_role(ctx, role, key)?;
+        Ok(())
+    }
+
+    pub fn revoke_role(ctx: Context<RevokeRole>, role: Role) -> Result<()> {
+        instructions::config::revoke_role(ctx, role)?;
+        Ok(())
+    }
+
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+        instructions::config::set_paused(ctx, paused)?;
+        Ok(())
+    }
+
 
     // ----- Entrant functions -----
 
@@ -31,11 +75,39 @@ pub mod shitcoin_raffle {
         Ok(())
     }
 
+    pub fn cancel_entrant(ctx: Context<CancelEntrant>) -> Result<()> {
+        instructions::entrant::cancel(ctx)?;
+        Ok(())
+    }
+
     pub fn close_entrant(ctx: Context<CloseEntrant>) -> Result<()> {
         instructions::entrant::close(ctx)?;
         Ok(())
     }
 
+    pub fn settle_entrants<'info>(ctx: Context<'_, '_, '_, 'info, SettleEntrants<'info>>) -> Result<()> {
+        instructions::entrant::settle(ctx)?;
+        Ok(())
+    }
+
+    pub fn set_shorten_opt_in(ctx: Context<SetShortenOptIn>, opt_in: bool) -> Result<()> {
+        instructions::entrant::set_shorten_opt_in(ctx, opt_in)?;
+        Ok(())
+    }
+
+
+    // ----- Referrer functions -----
+
+    pub fn init_referrer(ctx: Context<InitReferrer>) -> Result<()> {
+        instructions::referrer::initialize(ctx)?;
+        Ok(())
+    }
+
+    pub fn close_referrer(ctx: Context<CloseReferrer>) -> Result<()> {
+        instructions::referrer::close(ctx)?;
+        Ok(())
+    }
+
 
     // ----- Raffle functions -----
 
@@ -50,6 +122,11 @@ pub mod shitcoin_raffle {
         fee: Option<u64>,
         tickets: Option<u64>,
         limit: Option<u64>,
+        referral_share: Option<u16>,
+        extension_window: Option<i64>,
+        extension_duration: Option<i64>,
+        max_auto_extension: Option<i64>,
+        claim_period: Option<i64>,
     ) -> Result<()> {
         instructions::raffle::initialize(
             ctx,
@@ -62,6 +139,46 @@ pub mod shitcoin_raffle {
             fee,
             tickets,
             limit,
+            referral_share,
+            extension_window,
+            extension_duration,
+            max_auto_extension,
+            claim_period,
+        )?;
+        Ok(())
+    }
+
+    pub fn update_raffle(
+        ctx: Context<UpdateRaffle>,
+        price: u64,
+        rewards_num: u64,
+        rewards_amount: u64,
+        start_timestamp: i64,
+        end_timestamp: i64,
+        fee: Option<u64>,
+        tickets: Option<u64>,
+        limit: Option<u64>,
+        referral_share: Option<u16>,
+        extension_window: Option<i64>,
+        extension_duration: Option<i64>,
+        max_auto_extension: Option<i64>,
+        claim_period: Option<i64>,
+    ) -> Result<()> {
+        instructions::raffle::update(
+            ctx,
+            price,
+            rewards_num,
+            rewards_amount,
+            start_timestamp,
+            end_timestamp,
+            fee,
+            tickets,
+            limit,
+            referral_share,
+            extension_window,
+            extension_duration,
+            max_auto_extension,
+            claim_period,
         )?;
         Ok(())
     }
@@ -85,4 +202,57 @@ pub mod shitcoin_raffle {
         instructions::raffle::close(ctx)?;
         Ok(())
     }
+
+    pub fn sweep_unclaimed(ctx: Context<SweepUnclaimed>) -> Result<()> {
+        instructions::raffle::sweep_unclaimed(ctx)?;
+        Ok(())
+    }
+
+    pub fn sweep_excess(ctx: Context<SweepExcess>) -> Result<()> {
+        instructions::raffle::sweep_excess(ctx)?;
+        Ok(())
+    }
+
+    pub fn verify_solvency(ctx: Context<VerifySolvency>) -> Result<()> {
+        instructions::raffle::verify_solvency(ctx)?;
+        Ok(())
+    }
+
+    pub fn cancel_raffle(ctx: Context<CancelRaffle>) -> Result<()> {
+        instructions::raffle::cancel(ctx)?;
+        Ok(())
+    }
+
+    pub fn crank_status(ctx: Context<CrankStatus>) -> Result<()> {
+        instructions::raffle::crank_status(ctx)?;
+        Ok(())
+    }
+
+    pub fn set_end_timestamp(ctx: Context<SetEndTimestamp>, end_timestamp: i64) -> Result<()> {
+        instructions::raffle::set_end_timestamp(ctx, end_timestamp)?;
+        Ok(())
+    }
+
+    pub fn set_raffle_paused(ctx: Context<SetRafflePaused>, paused: bool, extend: bool) -> Result<()> {
+        instructions::raffle::set_paused(ctx, paused, extend)?;
+        Ok(())
+    }
+
+
+    // ----- Migration functions -----
+
+    pub fn migrate_admin_settings(ctx: Context<MigrateAdminSettings>) -> Result<()> {
+        instructions::migrate::migrate_admin_settings(ctx)?;
+        Ok(())
+    }
+
+    pub fn migrate_raffle(ctx: Context<MigrateRaffle>) -> Result<()> {
+        instructions::migrate::migrate_raffle(ctx)?;
+        Ok(())
+    }
+
+    pub fn migrate_entrant(ctx: Context<MigrateEntrant>) -> Result<()> {
+        instructions::migrate::migrate_entrant(ctx)?;
+        Ok(())
+    }
 }
\ No newline at end of file
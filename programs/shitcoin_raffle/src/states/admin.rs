@@ -1,11 +1,109 @@
 use anchor_lang::prelude::*;
 
+use crate::errors::*;
+
 #[account]
 pub struct AdminSettings {
-    
+
+    // Version of the account layout
+    pub version: u8,
+
     // Bump used in generating the AdminSettings account
     pub bump: u8,
-    
-    // Address of the admin's account
+
+    // Address of the admin's account, implicitly holds every role
     pub admin: Pubkey,
-} 
+
+    // Address proposed as the new admin, has to accept before taking over
+    pub pending_admin: Option<Pubkey>,
+
+    // Fee in lamports paid to the treasury for creating a raffle
+    pub creation_fee: u64,
+
+    // Maximum number of seconds a raffle's end timestamp can be extended by
+    pub max_extension: i64,
+
+    // Address allowed to create raffles without paying the creation fee
+    pub creator: Pubkey,
+
+    // Address allowed to set rewards of any raffle
+    pub drawer: Pubkey,
+
+    // Address allowed to claim proceeds and close any raffle, receives the protocol fees
+    pub treasurer: Pubkey,
+
+    // Address allowed to pause the program and individual raffles
+    pub pauser: Pubkey,
+
+    // Program is paused
+    pub paused: bool,
+
+    // Reserved space for future fields
+    pub reserved: [u8; 64],
+}
+
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Creator,
+    Drawer,
+    Treasurer,
+    Pauser,
+}
+
+
+impl AdminSettings {
+
+    // Current version of the account layout
+    pub const VERSION: u8 = 1;
+
+    // Borsh size of the account including the discriminator, new fields take their bytes from
+    // the reserved space so it never changes
+    pub const SPACE: usize = 8 // discriminator
+        + 1 + 1 // version, bump
+        + 32 // admin
+        + 1 + 32 // pending_admin
+        + 8 + 8 // creation_fee, max_extension
+        + 32 * 4 // creator, drawer, treasurer, pauser
+        + 1 // paused
+        + 64; // reserved
+
+    pub fn assert_not_paused(&self) -> Result<()> {
+        require!(
+            self.paused == false,
+            RaffleError::ProgramPaused
+        );
+
+        Ok(())
+    }
+
+    pub fn get_role(&self, role: Role) -> Pubkey {
+        match role {
+            Role::Creator => self.creator,
+            Role::Drawer => self.drawer,
+            Role::Treasurer => self.treasurer,
+            Role::Pauser => self.pauser,
+        }
+    }
+
+    pub fn set_role(&mut self, role: Role, key: Pubkey) {
+        match role {
+            Role::Creator => self.creator = key,
+            Role::Drawer => self.drawer = key,
+            Role::Treasurer => self.treasurer = key,
+            Role::Pauser => self.pauser = key,
+        }
+    }
+
+    pub fn has_role(&self, role: Role, key: &Pubkey) -> bool {
+        *key == self.admin || *key == self.get_role(role)
+    }
+
+    pub fn get_treasury(&self) -> Pubkey {
+        if self.treasurer == Pubkey::default() {
+            self.admin
+        } else {
+            self.treasurer
+        }
+    }
+}
@@ -5,6 +5,9 @@ use crate::errors::*;
 #[account]
 pub struct Entrant {
 
+    // Version of the account layout
+    pub version: u8,
+
     // Bump
     pub bump: u8,
 
@@ -19,11 +22,29 @@ pub struct Entrant {
 
     // Number of winning tickets
     pub rewards: u64,
+
+    // Entrant agreed to the raffle's end timestamp being shortened
+    pub shorten_opt_in: bool,
+
+    // Reserved space for future fields
+    pub reserved: [u8; 64],
 }
 
 
 impl Entrant {
 
+    // Current version of the account layout
+    pub const VERSION: u8 = 1;
+
+    // Borsh size of the account including the discriminator, new fields take their bytes from
+    // the reserved space so it never changes
+    pub const SPACE: usize = 8 // discriminator
+        + 1 + 1 // version, bump
+        + 32 + 32 // user, raffle
+        + 8 + 8 // tickets, rewards
+        + 1 // shorten_opt_in
+        + 64; // reserved
+
     pub fn assert_not_awarded(&self) -> Result<()> {
         require!(
             self.rewards == 0,
@@ -0,0 +1,157 @@
+use std::cmp;
+use anchor_lang::prelude::*;
+
+use crate::errors::*;
+use crate::states::*;
+
+
+/*
+ * Account layouts before versioning was introduced, only used for migrating accounts
+ *
+ * These are the layouts the program was first deployed with, fields added since are filled with
+ * defaults. Referrer accounts were introduced together with versioning and have no legacy layout.
+ */
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct AdminSettingsV0 {
+    pub bump: u8,
+    pub admin: Pubkey,
+}
+
+impl From<AdminSettingsV0> for AdminSettings {
+    fn from(legacy: AdminSettingsV0) -> Self {
+        AdminSettings {
+            version: AdminSettings::VERSION,
+            bump: legacy.bump,
+            admin: legacy.admin,
+            pending_admin: None,
+            creation_fee: 0,
+            max_extension: 0,
+            creator: Pubkey::default(),
+            drawer: Pubkey::default(),
+            treasurer: Pubkey::default(),
+            pauser: Pubkey::default(),
+            paused: false,
+            reserved: [0; 64],
+        }
+    }
+}
+
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct RaffleV0 {
+    pub bump: u8,
+    pub seed: Pubkey,
+    pub price: u64,
+    pub fee: u64,
+    pub currency: Pubkey,
+    pub rewards_num: u64,
+    pub rewards_amount: u64,
+    pub reward: Pubkey,
+    pub start_timestamp: i64,
+    pub end_timestamp: i64,
+    pub tickets: Option<u64>,
+    pub limit: Option<u64>,
+    pub tickets_sold: u64,
+    pub rewards_awarded: u64,
+    pub rewards_claimed: u64,
+    pub admin_claimed: bool,
+}
+
+impl RaffleV0 {
+
+    // Legacy raffles were created and claimed by the admin, who becomes their creator. Entrants
+    // weren't counted, so the raffle is marked as legacy. Refunds paid out before the migration are
+    // derived from the proceeds left in the vault, and the status from the schedule and draw
+    pub fn migrate(self, admin: Pubkey, proceeds: u64, now: i64) -> Result<Raffle> {
+        let mut raffle = Raffle {
+            version: Raffle::VERSION,
+            bump: self.bump,
+            seed: self.seed,
+            creator: admin,
+            price: self.price,
+            fee: self.fee,
+            currency: self.currency,
+            rewards_num: self.rewards_num,
+            rewards_amount: self.rewards_amount,
+            reward: self.reward,
+            start_timestamp: self.start_timestamp,
+            end_timestamp: self.end_timestamp,
+            initial_end_timestamp: self.end_timestamp,
+            extension_window: 0,
+            extension_duration: 0,
+            tickets: self.tickets,
+            limit: self.limit,
+            tickets_sold: self.tickets_sold,
+            entrants: 0,
+            shorten_opt_ins: 0,
+            rewards_awarded: self.rewards_awarded,
+            rewards_claimed: self.rewards_claimed,
+            rewards_forfeited: 0,
+            tickets_refunded: 0,
+            claim_period: None,
+            claim_deadline: None,
+            swept: false,
+            admin_claimed: self.admin_claimed,
+            status: RaffleStatus::Pending,
+            referral_share: 0,
+            referral_accrued: 0,
+            referral_claimed: 0,
+            paused: false,
+            paused_at: None,
+            max_auto_extension: 0,
+            legacy: true,
+            reserved: [0; 55],
+        };
+
+        raffle.tickets_refunded = get_refunded_tickets(&raffle, proceeds)?;
+
+        raffle.advance_at(now)?;
+        if raffle.admin_claimed && raffle.status == RaffleStatus::Drawn {
+            raffle.transition(RaffleStatus::Settled)?;
+        }
+
+        Ok(raffle)
+    }
+}
+
+// Legacy entrants were refunded when closed, the vault holds every ticket paid minus the claimed
+// proceeds and those refunds. Tickets refunded at zero cost owe nothing and count as refunded
+fn get_refunded_tickets(raffle: &Raffle, proceeds: u64) -> Result<u64> {
+    let refundable_tickets = raffle.tickets_sold.checked_sub(raffle.rewards_awarded).ok_or(RaffleError::InvalidCalculation)?;
+    let ticket_refund = raffle.get_refunable_proceeds(1)?;
+    if ticket_refund == 0 {
+        return Ok(refundable_tickets);
+    }
+
+    let paid = raffle.price.checked_mul(raffle.tickets_sold).ok_or(RaffleError::InvalidCalculation)?;
+    let claimed = if raffle.admin_claimed { raffle.get_authority_proceeds()? } else { 0 };
+    let refunded = paid.saturating_sub(claimed).saturating_sub(proceeds);
+
+    Ok(cmp::min(refunded / ticket_refund, refundable_tickets))
+}
+
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct EntrantV0 {
+    pub bump: u8,
+    pub user: Pubkey,
+    pub raffle: Pubkey,
+    pub tickets: u64,
+    pub rewards: u64,
+}
+
+impl From<EntrantV0> for Entrant {
+    fn from(legacy: EntrantV0) -> Self {
+        Entrant {
+            version: Entrant::VERSION,
+            bump: legacy.bump,
+            user: legacy.user,
+            raffle: legacy.raffle,
+            tickets: legacy.tickets,
+            rewards: legacy.rewards,
+            shorten_opt_in: false,
+            reserved: [0; 64],
+        }
+    }
+}
@@ -1,7 +1,11 @@
 pub mod admin;
 pub mod raffle;
 pub mod entrant;
+pub mod referrer;
+pub mod legacy;
 
 pub use admin::*;
 pub use raffle::*;
 pub use entrant::*;
+pub use referrer::*;
+pub use legacy::*;
@@ -2,16 +2,51 @@ use std::cmp;
 use anchor_lang::prelude::*;
 
 use crate::errors::*;
+use crate::validation::RaffleParams;
+
+// Denominator of the referral share expressed in basis points
+pub const REFERRAL_SHARE_DENOMINATOR: u64 = 10_000;
+
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RaffleStatus {
+    // Raffle has not started yet, parameters can still be updated
+    Pending,
+
+    // Tickets can be bought
+    Active,
+
+    // End timestamp passed or sold out, waiting for the rewards to be set
+    Ended,
+
+    // All rewards have been set, entrants can claim
+    Drawn,
+
+    // Creator has claimed the proceeds
+    Settled,
+
+    // Raffle was cancelled before any ticket was sold
+    Cancelled,
+
+    // Raffle ended without any ticket sold
+    Failed,
+}
 
 
 #[account]
 pub struct Raffle {
+    // Version of the account layout
+    pub version: u8,
+
     // Bump used in generating the Raffle account
     pub bump: u8,
 
     // Seed used in deriving the raffle account
     pub seed: Pubkey,
 
+    // Creator of the raffle, receives the proceeds
+    pub creator: Pubkey,
+
     // Entry price of the raffle
     pub price: u64,
 
@@ -36,6 +71,15 @@ pub struct Raffle {
     // Raffle end timestamp
     pub end_timestamp: i64,
 
+    // Raffle end timestamp before any extension
+    pub initial_end_timestamp: i64,
+
+    // Purchases within this many seconds before the end extend the raffle
+    pub extension_window: i64,
+
+    // Number of seconds the raffle is extended by on a late purchase
+    pub extension_duration: i64,
+
     // Number of tickets
     pub tickets: Option<u64>,
 
@@ -45,29 +89,136 @@ pub struct Raffle {
     // Number of tickets sold
     pub tickets_sold: u64,
 
+    // Number of entrants that bought tickets
+    pub entrants: u64,
+
+    // Number of entrants that agreed to the end timestamp being shortened
+    pub shorten_opt_ins: u64,
+
     // Number of awarded rewards
     pub rewards_awarded: u64,
 
     // Number of claimed rewards
     pub rewards_claimed: u64,
 
+    // Number of rewards forfeited after the claim deadline
+    pub rewards_forfeited: u64,
+
+    // Number of tickets refunded
+    pub tickets_refunded: u64,
+
+    // Number of seconds entrants have to claim after the rewards are set
+    pub claim_period: Option<i64>,
+
+    // Deadline for claiming, set once the rewards are set
+    pub claim_deadline: Option<i64>,
+
+    // Unclaimed funds have been swept to the treasury
+    pub swept: bool,
+
     // Admin has claimed the proceeds
     pub admin_claimed: bool,
+
+    // Lifecycle status of the raffle
+    pub status: RaffleStatus,
+
+    // Share of the fee paid to referrers, in basis points
+    pub referral_share: u16,
+
+    // Accrued referral rewards
+    pub referral_accrued: u64,
+
+    // Claimed referral rewards
+    pub referral_claimed: u64,
+
+    // Raffle is paused
+    pub paused: bool,
+
+    // Timestamp at which the raffle was paused
+    pub paused_at: Option<i64>,
+
+    // Number of seconds late purchases can extend the raffle past its initial end timestamp
+    pub max_auto_extension: i64,
+
+    // Raffle was migrated from the layout before versioning, which didn't count entrants
+    pub legacy: bool,
+
+    // Reserved space for future fields
+    pub reserved: [u8; 55],
 }
 
 impl Raffle {
 
-    pub fn assert_active(&self) -> Result<()> {
-        let clock = Clock::get()?;
+    // Current version of the account layout
+    pub const VERSION: u8 = 1;
+
+    // Borsh size of the account including the discriminator, new fields take their bytes from
+    // the reserved space so it never changes
+    pub const SPACE: usize = 8 // discriminator
+        + 1 + 1 // version, bump
+        + 32 + 32 // seed, creator
+        + 8 + 8 + 32 // price, fee, currency
+        + 8 + 8 + 32 // rewards_num, rewards_amount, reward
+        + 8 * 5 // start, end and initial end timestamps, extension window and duration
+        + 9 + 9 // tickets, limit
+        + 8 * 7 // tickets_sold, entrants, shorten_opt_ins, rewards and refund counters
+        + 9 + 9 // claim_period, claim_deadline
+        + 1 + 1 + 1 // swept, admin_claimed, status
+        + 2 + 8 + 8 // referral_share, referral_accrued, referral_claimed
+        + 1 + 9 // paused, paused_at
+        + 8 // max_auto_extension
+        + 1 // legacy
+        + 55; // reserved
+
+    // Parameters have to be validated by the caller
+    pub fn set_params(&mut self, params: &RaffleParams) {
+        self.price = params.price;
+        self.fee = params.fee.unwrap_or(0);
+        self.rewards_num = params.rewards_num;
+        self.rewards_amount = params.rewards_amount;
+        self.start_timestamp = params.start_timestamp;
+        self.end_timestamp = params.end_timestamp;
+        self.initial_end_timestamp = params.end_timestamp;
+        self.tickets = params.tickets;
+        self.limit = params.limit;
+        self.referral_share = params.referral_share.unwrap_or(0);
+        self.extension_window = params.extension_window.unwrap_or(0);
+        self.extension_duration = params.extension_duration.unwrap_or(0);
+        self.max_auto_extension = params.max_auto_extension.unwrap_or(0);
+        self.claim_period = params.claim_period;
+    }
 
+    pub fn assert_status(&self, status: RaffleStatus) -> Result<()> {
         require!(
-            clock.unix_timestamp >= self.start_timestamp,
-            RaffleError::RaffleNotStarted
+            self.status == status,
+            RaffleError::InvalidRaffleStatus
         );
 
+        Ok(())
+    }
+
+    pub fn assert_not_started(&self) -> Result<()> {
         require!(
-            clock.unix_timestamp < self.end_timestamp,
-            RaffleError::RaffleEnded
+            self.status == RaffleStatus::Pending,
+            RaffleError::RaffleAlreadyStarted
+        );
+
+        Ok(())
+    }
+
+    pub fn assert_not_paused(&self) -> Result<()> {
+        require!(
+            self.paused == false,
+            RaffleError::RafflePaused
+        );
+
+        Ok(())
+    }
+
+    pub fn assert_active(&self) -> Result<()> {
+        require!(
+            self.status != RaffleStatus::Pending,
+            RaffleError::RaffleNotStarted
         );
 
         require!(
@@ -75,14 +226,17 @@ impl Raffle {
             RaffleError::RaffleSoldOut
         );
 
+        require!(
+            self.status == RaffleStatus::Active,
+            RaffleError::RaffleEnded
+        );
+
         Ok(())
     }
 
     pub fn assert_ended(&self) -> Result<()> {
-        let clock = Clock::get()?;
-
         require!(
-            clock.unix_timestamp >= self.end_timestamp || self.tickets_sold == self.tickets.unwrap_or(u64::MAX),
+            self.status != RaffleStatus::Pending && self.status != RaffleStatus::Active,
             RaffleError::RaffleStillActive
         );
 
@@ -93,7 +247,7 @@ impl Raffle {
         self.assert_ended()?;
 
         require!(
-            self.rewards_awarded == cmp::min(self.rewards_num, self.tickets_sold),
+            self.status == RaffleStatus::Drawn || self.status == RaffleStatus::Settled,
             RaffleError::RaffleRewardsNotSet
         );
 
@@ -104,7 +258,7 @@ impl Raffle {
         self.assert_awarded()?;
 
         require!(
-            self.admin_claimed == false,
+            self.status == RaffleStatus::Drawn,
             RaffleError::RaffleAdminAlreadyClaimed
         );
 
@@ -112,27 +266,263 @@ impl Raffle {
     }
 
     pub fn assert_closeable(&self) -> Result<()> {
+        if self.status == RaffleStatus::Cancelled || self.status == RaffleStatus::Failed {
+            return Ok(());
+        }
+
         self.assert_awarded()?;
 
         require!(
-            self.admin_claimed == true,
+            self.status == RaffleStatus::Settled,
             RaffleError::RaffleAdminNotClaimed
         );
 
+        let rewards_settled = self.rewards_claimed.checked_add(self.rewards_forfeited).ok_or(RaffleError::InvalidCalculation)?;
         require!(
-            self.rewards_claimed == self.rewards_num,
+            rewards_settled == self.rewards_awarded,
             RaffleError::RaffleRewardsNotClaimed
         );
 
+        let tickets_settled = self.tickets_refunded.checked_add(self.rewards_awarded).ok_or(RaffleError::InvalidCalculation)?;
+        require!(
+            self.swept || tickets_settled == self.tickets_sold,
+            RaffleError::RaffleRefundsNotClaimed
+        );
+
+        require!(
+            self.swept || self.referral_claimed == self.referral_accrued,
+            RaffleError::RaffleReferralsNotClaimed
+        );
+
+        Ok(())
+    }
+
+    pub fn assert_sweepable(&self) -> Result<()> {
+        let clock = Clock::get()?;
+
+        self.assert_awarded()?;
+
+        require!(
+            self.swept == false,
+            RaffleError::RaffleAlreadySwept
+        );
+
+        require!(
+            clock.unix_timestamp >= self.claim_deadline.unwrap_or(i64::MAX),
+            RaffleError::ClaimDeadlineNotPassed
+        );
+
+        Ok(())
+    }
+
+    pub fn transition(&mut self, status: RaffleStatus) -> Result<()> {
+        let allowed = matches!(
+            (self.status, status),
+            (RaffleStatus::Pending, RaffleStatus::Active)
+                | (RaffleStatus::Pending, RaffleStatus::Cancelled)
+                | (RaffleStatus::Active, RaffleStatus::Ended)
+                | (RaffleStatus::Active, RaffleStatus::Cancelled)
+                | (RaffleStatus::Ended, RaffleStatus::Drawn)
+                | (RaffleStatus::Ended, RaffleStatus::Failed)
+                | (RaffleStatus::Drawn, RaffleStatus::Settled)
+        );
+
+        require!(
+            allowed,
+            RaffleError::InvalidStatusTransition
+        );
+
+        self.status = status;
+
+        Ok(())
+    }
+
+    pub fn advance(&mut self) -> Result<()> {
+        let clock = Clock::get()?;
+
+        self.advance_at(clock.unix_timestamp)
+    }
+
+    // A paused raffle keeps its status, time spent paused can be given back on unpause
+    pub fn advance_at(&mut self, now: i64) -> Result<()> {
+        if self.paused {
+            return Ok(());
+        }
+
+        if self.status == RaffleStatus::Pending && now >= self.start_timestamp {
+            self.transition(RaffleStatus::Active)?;
+        }
+
+        if self.status == RaffleStatus::Active
+            && (now >= self.end_timestamp || self.tickets_sold == self.tickets.unwrap_or(u64::MAX))
+        {
+            self.transition(RaffleStatus::Ended)?;
+        }
+
+        if self.status == RaffleStatus::Ended && self.tickets_sold == 0 {
+            self.transition(RaffleStatus::Failed)?;
+        }
+
+        if self.status == RaffleStatus::Ended && self.rewards_awarded == self.get_rewards_target() {
+            self.transition(RaffleStatus::Drawn)?;
+
+            if let Some(claim_period) = self.claim_period {
+                self.claim_deadline = Some(now.checked_add(claim_period).ok_or(RaffleError::InvalidCalculation)?);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn cancel(&mut self) -> Result<()> {
+        require!(
+            self.tickets_sold == 0,
+            RaffleError::RaffleHasTicketsSold
+        );
+
+        self.transition(RaffleStatus::Cancelled)
+    }
+    
+    pub fn set_end_timestamp(&mut self, end_timestamp: i64, max_extension: i64) -> Result<()> {
+        let clock = Clock::get()?;
+
+        require!(
+            clock.unix_timestamp < end_timestamp,
+            RaffleError::EndTimestampAlreadyPassed
+        );
+
+        let max_end_timestamp = self.initial_end_timestamp.checked_add(max_extension).ok_or(RaffleError::InvalidCalculation)?;
+        require!(
+            end_timestamp <= max_end_timestamp,
+            RaffleError::ExtensionTooLarge
+        );
+
+        require!(
+            end_timestamp >= self.end_timestamp
+                || self.tickets_sold == 0
+                || (!self.legacy && self.shorten_opt_ins == self.entrants),
+            RaffleError::ShorteningNotAllowed
+        );
+
+        self.end_timestamp = end_timestamp;
+
+        Ok(())
+    }
+
+    pub fn auto_extend(&mut self) -> Result<()> {
+        let clock = Clock::get()?;
+
+        let window_start = self.end_timestamp.checked_sub(self.extension_window).ok_or(RaffleError::InvalidCalculation)?;
+        if self.extension_duration == 0 || clock.unix_timestamp < window_start {
+            return Ok(());
+        }
+
+        let extended_end_timestamp = self.end_timestamp.checked_add(self.extension_duration).ok_or(RaffleError::InvalidCalculation)?;
+        let max_end_timestamp = self.initial_end_timestamp.checked_add(self.max_auto_extension).ok_or(RaffleError::InvalidCalculation)?;
+        self.end_timestamp = cmp::max(self.end_timestamp, cmp::min(extended_end_timestamp, max_end_timestamp));
+
+        Ok(())
+    }
+
+    // Pausing an already paused raffle keeps the first pause timestamp, so unpause can still extend
+    // by the whole time spent paused
+    pub fn pause(&mut self) -> Result<()> {
+        let clock = Clock::get()?;
+
+        if !self.paused {
+            self.paused = true;
+            self.paused_at = Some(clock.unix_timestamp);
+        }
+
+        Ok(())
+    }
+
+    // Time spent paused is only given back to a raffle still selling tickets, and never past the
+    // larger of the manual and automatic extension limits
+    pub fn unpause(&mut self, extend: bool, max_extension: i64) -> Result<()> {
+        let clock = Clock::get()?;
+
+        let selling = self.status == RaffleStatus::Pending || self.status == RaffleStatus::Active;
+        if let (true, true, Some(paused_at)) = (extend, selling, self.paused_at) {
+            let paused_duration = clock.unix_timestamp.checked_sub(paused_at).ok_or(RaffleError::InvalidCalculation)?;
+            let extended_end_timestamp = self.end_timestamp.checked_add(paused_duration).ok_or(RaffleError::InvalidCalculation)?;
+            let max_extension = cmp::max(max_extension, self.max_auto_extension);
+            let max_end_timestamp = self.initial_end_timestamp.checked_add(max_extension).ok_or(RaffleError::InvalidCalculation)?;
+            self.end_timestamp = cmp::max(self.end_timestamp, cmp::min(extended_end_timestamp, max_end_timestamp));
+        }
+
+        self.paused = false;
+        self.paused_at = None;
+
         Ok(())
     }
     
+    pub fn award_rewards(&mut self, tickets: u64) -> Result<()> {
+        let rewards_awarded = self.rewards_awarded.checked_add(tickets).ok_or(RaffleError::InvalidCalculation)?;
+
+        require!(
+            rewards_awarded <= self.get_rewards_target(),
+            RaffleError::RewardsAmountGreaterThanTotal
+        );
+
+        self.rewards_awarded = rewards_awarded;
+
+        Ok(())
+    }
+
+    // Undersubscribed raffles award every sold ticket, the surplus rewards are never minted
+    pub fn get_rewards_target(&self) -> u64 {
+        cmp::min(self.rewards_num, self.tickets_sold)
+    }
+
     pub fn claim_rewards(&mut self, tickets: u64) -> Result<()> {
         self.rewards_claimed = self.rewards_claimed.checked_add(tickets).ok_or(RaffleError::InvalidCalculation)?;
         
         Ok(())
     }
 
+    pub fn accrue_referral(&mut self, amount: u64) -> Result<()> {
+        self.referral_accrued = self.referral_accrued.checked_add(amount).ok_or(RaffleError::InvalidCalculation)?;
+
+        Ok(())
+    }
+
+    pub fn claim_referral(&mut self, amount: u64) -> Result<()> {
+        self.referral_claimed = self.referral_claimed.checked_add(amount).ok_or(RaffleError::InvalidCalculation)?;
+
+        Ok(())
+    }
+
+    pub fn get_referral_amount(&self, tickets: u64) -> Result<u64> {
+        let fee_proceeds = self.fee.checked_mul(tickets).ok_or(RaffleError::InvalidCalculation)?;
+        let referral_amount = fee_proceeds
+            .checked_mul(self.referral_share as u64).ok_or(RaffleError::InvalidCalculation)?
+            .checked_div(REFERRAL_SHARE_DENOMINATOR).ok_or(RaffleError::InvalidCalculation)?;
+
+        Ok(referral_amount)
+    }
+
+    pub fn refund_tickets(&mut self, tickets: u64) -> Result<()> {
+        self.tickets_refunded = self.tickets_refunded.checked_add(tickets).ok_or(RaffleError::InvalidCalculation)?;
+
+        Ok(())
+    }
+
+    pub fn sweep(&mut self) -> Result<u64> {
+        let refundable_tickets = self.tickets_sold
+            .checked_sub(self.rewards_awarded).ok_or(RaffleError::InvalidCalculation)?
+            .checked_sub(self.tickets_refunded).ok_or(RaffleError::InvalidCalculation)?;
+        let unclaimed_refunds = self.get_refunable_proceeds(refundable_tickets)?;
+        let unclaimed_referrals = self.referral_accrued.checked_sub(self.referral_claimed).ok_or(RaffleError::InvalidCalculation)?;
+
+        self.rewards_forfeited = self.rewards_awarded.checked_sub(self.rewards_claimed).ok_or(RaffleError::InvalidCalculation)?;
+        self.swept = true;
+
+        let swept_amount = unclaimed_refunds.checked_add(unclaimed_referrals).ok_or(RaffleError::InvalidCalculation)?;
+
+        Ok(swept_amount)
+    }
+
     pub fn get_reward_amount(&self, tickets: u64) -> Result<u64> {
         let reward_amount = self.rewards_amount.checked_mul(tickets).ok_or(RaffleError::InvalidCalculation)?;
 
@@ -143,7 +533,9 @@ impl Raffle {
         let refundable_proceeds = self.price.checked_mul(tickets).ok_or(RaffleError::InvalidCalculation)?;
         let fee_proceeds = self.fee.checked_mul(tickets).ok_or(RaffleError::InvalidCalculation)?;
 
-        Ok(refundable_proceeds - fee_proceeds)
+        let refundable_proceeds = refundable_proceeds.checked_sub(fee_proceeds).ok_or(RaffleError::InvalidCalculation)?;
+
+        Ok(refundable_proceeds)
     }
 
     pub fn get_authority_proceeds(&self) -> Result<u64> {
@@ -151,7 +543,176 @@ impl Raffle {
         let refundable_tickets = self.tickets_sold.checked_sub(self.rewards_awarded).ok_or(RaffleError::InvalidCalculation)?;
         let fee_proceeds = self.fee.checked_mul(refundable_tickets).ok_or(RaffleError::InvalidCalculation)?;
         let total_proceeds = non_refundable_proceeds.checked_add(fee_proceeds).ok_or(RaffleError::InvalidCalculation)?;
+        let total_proceeds = total_proceeds.checked_sub(self.referral_accrued).ok_or(RaffleError::InvalidCalculation)?;
 
         Ok(total_proceeds)
     }
+
+    // Proceeds the vault still owes: unpaid refunds, the creator's unclaimed share and unclaimed
+    // referral rewards. Refunds and referral rewards are no longer owed once swept
+    pub fn get_liabilities(&self) -> Result<u64> {
+        let authority_proceeds = if self.admin_claimed { 0 } else { self.get_authority_proceeds()? };
+        if self.swept {
+            return Ok(authority_proceeds);
+        }
+
+        let refundable_tickets = self.tickets_sold
+            .checked_sub(self.rewards_awarded).ok_or(RaffleError::InvalidCalculation)?
+            .checked_sub(self.tickets_refunded).ok_or(RaffleError::InvalidCalculation)?;
+        let unpaid_refunds = self.get_refunable_proceeds(refundable_tickets)?;
+        let unclaimed_referrals = self.referral_accrued.checked_sub(self.referral_claimed).ok_or(RaffleError::InvalidCalculation)?;
+
+        let liabilities = authority_proceeds
+            .checked_add(unpaid_refunds).ok_or(RaffleError::InvalidCalculation)?
+            .checked_add(unclaimed_referrals).ok_or(RaffleError::InvalidCalculation)?;
+
+        Ok(liabilities)
+    }
+}
+
+
+// Pending raffle with zeroed parameters and counters, fixtures override the fields they need
+#[cfg(any(test, feature = "test-utils"))]
+impl Default for Raffle {
+    fn default() -> Self {
+        Raffle {
+            version: Raffle::VERSION,
+            bump: 255,
+            seed: Pubkey::default(),
+            creator: Pubkey::default(),
+            price: 0,
+            fee: 0,
+            currency: Pubkey::default(),
+            rewards_num: 0,
+            rewards_amount: 0,
+            reward: Pubkey::default(),
+            start_timestamp: 0,
+            end_timestamp: 0,
+            initial_end_timestamp: 0,
+            extension_window: 0,
+            extension_duration: 0,
+            tickets: None,
+            limit: None,
+            tickets_sold: 0,
+            entrants: 0,
+            shorten_opt_ins: 0,
+            rewards_awarded: 0,
+            rewards_claimed: 0,
+            rewards_forfeited: 0,
+            tickets_refunded: 0,
+            claim_period: None,
+            claim_deadline: None,
+            swept: false,
+            admin_claimed: false,
+            status: RaffleStatus::Pending,
+            referral_share: 0,
+            referral_accrued: 0,
+            referral_claimed: 0,
+            paused: false,
+            paused_at: None,
+            max_auto_extension: 0,
+            legacy: false,
+            reserved: [0; 55],
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn undersubscribed_raffle() -> Raffle {
+        Raffle {
+            price: 100,
+            fee: 10,
+            rewards_num: 5,
+            rewards_amount: 1_000,
+            end_timestamp: 100,
+            initial_end_timestamp: 100,
+            tickets_sold: 2,
+            entrants: 2,
+            status: RaffleStatus::Active,
+            ..Raffle::default()
+        }
+    }
+
+    #[test]
+    fn undersubscribed_raffle_is_drawn_once_every_ticket_is_awarded() {
+        let mut raffle = undersubscribed_raffle();
+
+        raffle.advance_at(100).unwrap();
+        assert_eq!(raffle.status, RaffleStatus::Ended);
+        assert_eq!(raffle.get_rewards_target(), 2);
+
+        raffle.award_rewards(1).unwrap();
+        raffle.advance_at(100).unwrap();
+        assert_eq!(raffle.status, RaffleStatus::Ended);
+
+        raffle.award_rewards(1).unwrap();
+        raffle.advance_at(100).unwrap();
+        assert_eq!(raffle.status, RaffleStatus::Drawn);
+    }
+
+    #[test]
+    fn space_is_the_borsh_size_of_the_largest_raffle() {
+        let raffle = Raffle {
+            tickets: Some(0),
+            limit: Some(0),
+            claim_period: Some(0),
+            claim_deadline: Some(0),
+            paused_at: Some(0),
+            ..Raffle::default()
+        };
+
+        assert_eq!(8 + raffle.try_to_vec().unwrap().len(), Raffle::SPACE);
+    }
+
+    #[test]
+    fn paused_raffle_does_not_advance() {
+        let mut raffle = undersubscribed_raffle();
+        raffle.paused = true;
+
+        raffle.advance_at(100).unwrap();
+        assert_eq!(raffle.status, RaffleStatus::Active);
+
+        raffle.paused = false;
+        raffle.advance_at(100).unwrap();
+        assert_eq!(raffle.status, RaffleStatus::Ended);
+    }
+
+    #[test]
+    fn undersubscribed_raffle_rejects_rewards_beyond_tickets_sold() {
+        let mut raffle = undersubscribed_raffle();
+        raffle.advance_at(100).unwrap();
+
+        raffle.award_rewards(2).unwrap();
+        assert!(raffle.award_rewards(1).is_err());
+        assert_eq!(raffle.rewards_awarded, 2);
+    }
+
+    #[test]
+    fn undersubscribed_raffle_is_closeable_once_awarded_rewards_are_claimed() {
+        let mut raffle = undersubscribed_raffle();
+        raffle.advance_at(100).unwrap();
+        raffle.award_rewards(2).unwrap();
+        raffle.advance_at(100).unwrap();
+
+        raffle.admin_claimed = true;
+        raffle.transition(RaffleStatus::Settled).unwrap();
+        assert!(raffle.assert_closeable().is_err());
+
+        raffle.claim_rewards(2).unwrap();
+        raffle.assert_closeable().unwrap();
+    }
+
+    #[test]
+    fn undersubscribed_raffle_pays_every_ticket_to_the_creator() {
+        let mut raffle = undersubscribed_raffle();
+        raffle.advance_at(100).unwrap();
+        raffle.award_rewards(2).unwrap();
+
+        assert_eq!(raffle.get_authority_proceeds().unwrap(), 200);
+        assert_eq!(raffle.get_refunable_proceeds(raffle.tickets_sold - raffle.rewards_awarded).unwrap(), 0);
+    }
 }
@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+
+#[account]
+pub struct Referrer {
+
+    // Version of the account layout
+    pub version: u8,
+
+    // Bump
+    pub bump: u8,
+
+    // User
+    pub user: Pubkey,
+
+    // Raffle
+    pub raffle: Pubkey,
+
+    // Number of referred tickets
+    pub tickets: u64,
+
+    // Accrued referral rewards
+    pub accrued: u64,
+
+    // Reserved space for future fields
+    pub reserved: [u8; 64],
+}
+
+
+impl Referrer {
+
+    // Current version of the account layout
+    pub const VERSION: u8 = 1;
+
+    // Borsh size of the account including the discriminator, new fields take their bytes from
+    // the reserved space so it never changes
+    pub const SPACE: usize = 8 // discriminator
+        + 1 + 1 // version, bump
+        + 32 + 32 // user, raffle
+        + 8 + 8 // tickets, accrued
+        + 64; // reserved
+}
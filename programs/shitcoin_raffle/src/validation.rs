@@ -0,0 +1,167 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::*;
+use crate::states::REFERRAL_SHARE_DENOMINATOR;
+
+
+/*
+ * Raffle parameters of init_raffle and update_raffle
+ *
+ * Validated by the program and by off-chain tooling before anything is sent, both go through
+ * `validate` so the checks can't drift apart.
+ */
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RaffleParams {
+
+    // Entry price of the raffle
+    pub price: u64,
+
+    // Number of rewards
+    pub rewards_num: u64,
+
+    // Reward amount
+    pub rewards_amount: u64,
+
+    // Raffle start timestamp
+    pub start_timestamp: i64,
+
+    // Raffle end timestamp
+    pub end_timestamp: i64,
+
+    // Non-refundable fee
+    pub fee: Option<u64>,
+
+    // Number of tickets
+    pub tickets: Option<u64>,
+
+    // Maximum number of tickets that can be purchased by single user
+    pub limit: Option<u64>,
+
+    // Share of the fee paid to referrers, in basis points
+    pub referral_share: Option<u16>,
+
+    // Purchases within this many seconds before the end extend the raffle
+    pub extension_window: Option<i64>,
+
+    // Number of seconds the raffle is extended by on a late purchase
+    pub extension_duration: Option<i64>,
+
+    // Number of seconds late purchases can extend the raffle past its initial end timestamp
+    pub max_auto_extension: Option<i64>,
+
+    // Number of seconds entrants have to claim after the rewards are set
+    pub claim_period: Option<i64>,
+}
+
+
+impl RaffleParams {
+
+    pub fn validate(&self, now: i64) -> Result<()> {
+        require!(
+            self.price > self.fee.unwrap_or(0),
+            RaffleError::FeeGreaterThanPrice
+        );
+
+        require!(
+            self.rewards_num < self.tickets.unwrap_or(u64::MAX),
+            RaffleError::RewardsNumGreaterThanTickets
+        );
+
+        require!(
+            self.limit.unwrap_or(1) > 0,
+            RaffleError::LimitLessThanOne
+        );
+
+        require!(
+            self.referral_share.unwrap_or(0) as u64 <= REFERRAL_SHARE_DENOMINATOR,
+            RaffleError::ReferralShareTooLarge
+        );
+
+        require!(
+            self.extension_window.unwrap_or(0) >= 0
+                && self.extension_duration.unwrap_or(0) >= 0
+                && self.max_auto_extension.unwrap_or(0) >= 0,
+            RaffleError::InvalidExtension
+        );
+
+        require!(
+            self.claim_period.unwrap_or(0) >= 0,
+            RaffleError::InvalidClaimPeriod
+        );
+
+        require!(
+            self.start_timestamp < self.end_timestamp,
+            RaffleError::StartAfterEndTimestamp
+        );
+
+        require!(
+            now < self.end_timestamp,
+            RaffleError::EndTimestampAlreadyPassed
+        );
+
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params() -> RaffleParams {
+        RaffleParams {
+            price: 100,
+            rewards_num: 5,
+            rewards_amount: 1_000,
+            start_timestamp: 100,
+            end_timestamp: 200,
+            fee: Some(10),
+            tickets: Some(50),
+            limit: Some(5),
+            ..RaffleParams::default()
+        }
+    }
+
+    #[test]
+    fn valid_params_pass() {
+        params().validate(0).unwrap();
+        RaffleParams { fee: None, tickets: None, limit: None, ..params() }.validate(0).unwrap();
+    }
+
+    #[test]
+    fn fee_must_be_smaller_than_price() {
+        let err = RaffleParams { fee: Some(100), ..params() }.validate(0).unwrap_err();
+        assert_eq!(err, RaffleError::FeeGreaterThanPrice.into());
+    }
+
+    #[test]
+    fn rewards_num_must_be_smaller_than_tickets() {
+        let err = RaffleParams { tickets: Some(5), ..params() }.validate(0).unwrap_err();
+        assert_eq!(err, RaffleError::RewardsNumGreaterThanTickets.into());
+    }
+
+    #[test]
+    fn limit_must_be_positive() {
+        let err = RaffleParams { limit: Some(0), ..params() }.validate(0).unwrap_err();
+        assert_eq!(err, RaffleError::LimitLessThanOne.into());
+    }
+
+    #[test]
+    fn extension_must_not_be_negative() {
+        let err = RaffleParams { max_auto_extension: Some(-1), ..params() }.validate(0).unwrap_err();
+        assert_eq!(err, RaffleError::InvalidExtension.into());
+    }
+
+    #[test]
+    fn start_must_be_before_end() {
+        let err = RaffleParams { start_timestamp: 200, ..params() }.validate(0).unwrap_err();
+        assert_eq!(err, RaffleError::StartAfterEndTimestamp.into());
+    }
+
+    #[test]
+    fn end_must_be_in_the_future() {
+        let err = params().validate(200).unwrap_err();
+        assert_eq!(err, RaffleError::EndTimestampAlreadyPassed.into());
+    }
+}
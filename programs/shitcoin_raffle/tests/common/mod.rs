@@ -0,0 +1,445 @@
+#![allow(dead_code)]
+
+use std::sync::Mutex;
+
+use anchor_lang::event::EVENT_IX_TAG_LE;
+use anchor_lang::solana_program::account_info::AccountInfo;
+use anchor_lang::solana_program::bpf_loader_upgradeable::{self, UpgradeableLoaderState};
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::{system_program, AccountDeserialize, AnchorDeserialize, AnchorSerialize, Discriminator};
+use anchor_spl::associated_token::spl_associated_token_account;
+use anchor_spl::token_2022::spl_token_2022;
+use anchor_spl::token_2022::spl_token_2022::extension::{StateWithExtensions, StateWithExtensionsMut};
+use shitcoin_raffle_client::instructions::{self, RaffleParams};
+use shitcoin_raffle_client::{pda, AdminSettings, Raffle, ID};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::account::{Account, AccountSharedData};
+use solana_sdk::clock::Clock;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::{Instruction, InstructionError};
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::{Transaction, TransactionError};
+
+
+/*
+ * Shared harness of the in-process integration tests
+ *
+ * The program runs natively inside a solana-program-test bank, next to the Token-2022 and
+ * associated token programs bundled with it. The clock only moves when a test warps it, every
+ * transaction is sent in a new slot so identical transactions never share a signature.
+ *
+ * The bank doesn't return inner instructions, so the entrypoint is wrapped to record the event CPIs
+ * the program makes.
+ *
+ * NotEnoughTicketsLeft and EntrantNotAwarded are not returned by any instruction, every other
+ * RaffleError is covered by the tests.
+ */
+
+// Clock at the start of every test
+pub const NOW: i64 = 1_700_000_000;
+
+// Default raffle schedule
+pub const START: i64 = NOW + 100;
+pub const END: i64 = NOW + 1_000;
+
+// Default raffle pricing
+pub const PRICE: u64 = 100;
+pub const FEE: u64 = 10;
+pub const REWARDS_NUM: u64 = 2;
+pub const REWARDS_AMOUNT: u64 = 1_000;
+
+// Decimals of the currency and reward mints
+pub const DECIMALS: u8 = 6;
+
+// Currency minted to every user
+pub const BALANCE: u64 = 1_000_000;
+
+pub fn params() -> RaffleParams {
+    RaffleParams {
+        price: PRICE,
+        rewards_num: REWARDS_NUM,
+        rewards_amount: REWARDS_AMOUNT,
+        start_timestamp: START,
+        end_timestamp: END,
+        fee: Some(FEE),
+        ..RaffleParams::default()
+    }
+}
+
+pub fn assert_error(result: Result<(), BanksClientError>, code: impl Into<u32>) {
+    let code = code.into();
+    match result.expect_err("transaction succeeded").unwrap() {
+        TransactionError::InstructionError(_, InstructionError::Custom(actual)) => assert_eq!(actual, code),
+        err => panic!("expected custom error {}, got {:?}", code, err),
+    }
+}
+
+
+/*
+ * Bank wrapper sending transactions paid by the test payer
+ */
+
+pub struct Bank {
+    pub context: ProgramTestContext,
+
+    // Unix timestamp the clock is held at across slots
+    pub unix_timestamp: i64,
+}
+
+impl Bank {
+
+    pub fn payer(&self) -> Pubkey {
+        self.context.payer.pubkey()
+    }
+
+    pub async fn send(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> Result<(), BanksClientError> {
+        let blockhash = self.next_slot().await;
+
+        let mut keypairs = vec![&self.context.payer];
+        keypairs.extend_from_slice(signers);
+        let tx = Transaction::new_signed_with_payer(instructions, Some(&self.context.payer.pubkey()), &keypairs, blockhash);
+
+        self.context.banks_client.process_transaction(tx).await
+    }
+
+    // Moves the clock to the given unix timestamp, the slot stays the same
+    pub async fn warp(&mut self, unix_timestamp: i64) {
+        self.unix_timestamp = unix_timestamp;
+        self.set_clock().await;
+    }
+
+    pub fn now(&self) -> i64 {
+        self.unix_timestamp
+    }
+
+    // Advances the bank by one slot and returns its blockhash, warping to a slot is much faster
+    // than waiting for a new blockhash but resets the clock
+    async fn next_slot(&mut self) -> Hash {
+        let root_slot = self.context.banks_client.get_root_slot().await.unwrap();
+        self.context.warp_to_slot(root_slot + 2).unwrap();
+        self.set_clock().await;
+
+        self.context.banks_client.get_latest_blockhash().await.unwrap()
+    }
+
+    async fn set_clock(&mut self) {
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp = self.unix_timestamp;
+        self.context.set_sysvar(&clock);
+    }
+
+    pub async fn exists(&mut self, address: &Pubkey) -> bool {
+        self.context.banks_client.get_account(*address).await.unwrap().is_some()
+    }
+
+    pub async fn lamports(&mut self, address: &Pubkey) -> u64 {
+        self.context.banks_client.get_balance(*address).await.unwrap()
+    }
+
+    pub async fn data(&mut self, address: &Pubkey) -> Vec<u8> {
+        let account = self.context.banks_client.get_account(*address).await.unwrap();
+        account.unwrap_or_else(|| panic!("account {} not found", address)).data
+    }
+
+    pub async fn account<T: AccountDeserialize>(&mut self, address: &Pubkey) -> T {
+        let data = self.data(address).await;
+        T::try_deserialize(&mut &data[..]).unwrap()
+    }
+
+    pub async fn token_balance(&mut self, address: &Pubkey) -> u64 {
+        let data = self.data(address).await;
+        StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data).unwrap().base.amount
+    }
+
+    pub async fn mint_supply(&mut self, mint: &Pubkey) -> u64 {
+        let data = self.data(mint).await;
+        StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data).unwrap().base.supply
+    }
+
+    pub async fn fund(&mut self, address: &Pubkey, lamports: u64) {
+        let ix = system_instruction::transfer(&self.payer(), address, lamports);
+        self.send(&[ix], &[]).await.unwrap();
+    }
+
+    pub async fn create_mint(&mut self, authority: &Pubkey) -> Pubkey {
+        let mint = Keypair::new();
+        let rent = self.context.banks_client.get_rent().await.unwrap();
+        let space = spl_token_2022::state::Mint::LEN;
+
+        let ixs = [
+            system_instruction::create_account(
+                &self.payer(),
+                &mint.pubkey(),
+                rent.minimum_balance(space),
+                space as u64,
+                &spl_token_2022::ID,
+            ),
+            spl_token_2022::instruction::initialize_mint2(&spl_token_2022::ID, &mint.pubkey(), authority, None, DECIMALS).unwrap(),
+        ];
+        self.send(&ixs, &[&mint]).await.unwrap();
+
+        mint.pubkey()
+    }
+
+    // Creates the owner's associated token account unless it already exists
+    pub async fn create_token_account(&mut self, owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+        let ix = spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+            &self.payer(),
+            owner,
+            mint,
+            &spl_token_2022::ID,
+        );
+        self.send(&[ix], &[]).await.unwrap();
+
+        pda::token_account(owner, mint)
+    }
+
+    // Mints to the owner's associated token account, the mint authority has to be the payer
+    pub async fn mint_to(&mut self, mint: &Pubkey, owner: &Pubkey, amount: u64) {
+        let ix = spl_token_2022::instruction::mint_to(
+            &spl_token_2022::ID,
+            mint,
+            &pda::token_account(owner, mint),
+            &self.payer(),
+            &[],
+            amount,
+        )
+        .unwrap();
+        self.send(&[ix], &[]).await.unwrap();
+    }
+
+    // Sends tokens from the payer's associated token account
+    pub async fn transfer_tokens(&mut self, mint: &Pubkey, to: &Pubkey, amount: u64) {
+        let payer = self.payer();
+        self.create_token_account(&payer, mint).await;
+        self.mint_to(mint, &payer, amount).await;
+
+        let ix = spl_token_2022::instruction::transfer_checked(
+            &spl_token_2022::ID,
+            &pda::token_account(&payer, mint),
+            mint,
+            to,
+            &payer,
+            &[],
+            amount,
+            DECIMALS,
+        )
+        .unwrap();
+        self.send(&[ix], &[]).await.unwrap();
+    }
+
+    // Overwrites the amount held by a token account, bypassing the token program
+    pub async fn set_token_balance(&mut self, address: &Pubkey, amount: u64) {
+        let mut account = self.context.banks_client.get_account(*address).await.unwrap().unwrap();
+        let mut state = StateWithExtensionsMut::<spl_token_2022::state::Account>::unpack(&mut account.data).unwrap();
+        state.base.amount = amount;
+        state.pack_base();
+
+        self.context.set_account(address, &AccountSharedData::from(account));
+    }
+
+    // Rewrites a program account of type T in its layout from before versioning
+    pub async fn set_legacy_account<T: Discriminator>(&mut self, address: &Pubkey, legacy: &impl AnchorSerialize) {
+        let data = [&T::discriminator()[..], &legacy.try_to_vec().unwrap()[..]].concat();
+        let rent = self.context.banks_client.get_rent().await.unwrap();
+
+        let account = Account {
+            lamports: rent.minimum_balance(data.len()),
+            data,
+            owner: ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        self.context.set_account(address, &AccountSharedData::from(account));
+    }
+}
+
+
+/*
+ * Test environment with an initialized program, an admin and a raffle creator
+ */
+
+pub struct Test {
+    pub bank: Bank,
+
+    // Upgrade authority of the program and admin
+    pub admin: Keypair,
+
+    // Creator of the test raffles
+    pub creator: Keypair,
+
+    // Currency mint, minted by the payer
+    pub currency: Pubkey,
+}
+
+pub struct TestRaffle {
+    pub address: Pubkey,
+    pub reward: Pubkey,
+}
+
+impl Test {
+
+    pub async fn new() -> Self {
+        let admin = Keypair::new();
+        let creator = Keypair::new();
+
+        let mut program_test = ProgramTest::new("shitcoin_raffle", ID, processor!(entry));
+        program_test.add_account(pda::program_data().0, program_data(&admin.pubkey()));
+        for wallet in [admin.pubkey(), creator.pubkey()] {
+            program_test.add_account(wallet, Account::new(100 * LAMPORTS_PER_SOL, 0, &system_program::ID));
+        }
+
+        let mut bank = Bank { context: program_test.start_with_context().await, unix_timestamp: NOW };
+        bank.warp(NOW).await;
+
+        let payer = bank.payer();
+        let currency = bank.create_mint(&payer).await;
+        bank.create_token_account(&creator.pubkey(), &currency).await;
+
+        let ix = instructions::init_admin(&admin.pubkey(), &admin.pubkey());
+        bank.send(&[ix], &[&admin]).await.unwrap();
+
+        Test { bank, admin, creator, currency }
+    }
+
+    // Funded wallet holding currency
+    pub async fn user(&mut self) -> Keypair {
+        let user = Keypair::new();
+        self.bank.fund(&user.pubkey(), 10 * LAMPORTS_PER_SOL).await;
+        self.bank.create_token_account(&user.pubkey(), &self.currency).await;
+        self.bank.mint_to(&self.currency, &user.pubkey(), BALANCE).await;
+
+        user
+    }
+
+    pub async fn admin_settings(&mut self) -> AdminSettings {
+        self.bank.account(&pda::admin_settings().0).await
+    }
+
+    pub async fn raffle(&mut self, raffle: &TestRaffle) -> Raffle {
+        self.bank.account(&raffle.address).await
+    }
+
+    pub async fn try_create_raffle(&mut self, params: &RaffleParams) -> (TestRaffle, Result<(), BanksClientError>) {
+        let seed = Pubkey::new_unique();
+        let address = pda::raffle(&seed).0;
+        let reward = self.bank.create_mint(&address).await;
+        let treasury = self.admin_settings().await.get_treasury();
+
+        let ix = instructions::init_raffle(&seed, &self.currency, &reward, &treasury, &self.creator.pubkey(), params);
+        let result = self.bank.send(&[ix], &[&self.creator]).await;
+
+        (TestRaffle { address, reward }, result)
+    }
+
+    pub async fn create_raffle(&mut self, params: &RaffleParams) -> TestRaffle {
+        let (raffle, result) = self.try_create_raffle(params).await;
+        result.unwrap();
+
+        raffle
+    }
+
+    // Creates the entrant and buys tickets in one transaction
+    pub async fn enter(&mut self, raffle: &TestRaffle, user: &Keypair, tickets: u64) -> Result<(), BanksClientError> {
+        self.bank.create_token_account(&user.pubkey(), &raffle.reward).await;
+
+        let ixs = [
+            instructions::init_entrant(&raffle.address, &user.pubkey()),
+            instructions::buy_tickets(&raffle.address, &self.currency, &user.pubkey(), None, tickets),
+        ];
+        self.bank.send(&ixs, &[user]).await
+    }
+
+    pub async fn buy(
+        &mut self,
+        raffle: &TestRaffle,
+        user: &Keypair,
+        referrer: Option<&Pubkey>,
+        tickets: u64,
+    ) -> Result<(), BanksClientError> {
+        let ix = instructions::buy_tickets(&raffle.address, &self.currency, &user.pubkey(), referrer, tickets);
+        self.bank.send(&[ix], &[user]).await
+    }
+
+    pub async fn set_reward(&mut self, raffle: &TestRaffle, user: &Pubkey, amount: u64) -> Result<(), BanksClientError> {
+        let ix = instructions::set_reward(&raffle.address, &self.creator.pubkey(), user, amount);
+        self.bank.send(&[ix], &[&self.creator]).await
+    }
+
+    pub async fn claim_proceeds(&mut self, raffle: &TestRaffle) -> Result<(), BanksClientError> {
+        let creator = self.creator.pubkey();
+        let ix = instructions::claim_proceeds(&raffle.address, &self.currency, &creator, &creator);
+        self.bank.send(&[ix], &[&self.creator]).await
+    }
+
+    pub async fn close_entrant(&mut self, raffle: &TestRaffle, user: &Keypair) -> Result<(), BanksClientError> {
+        let ix = instructions::close_entrant(&raffle.address, &self.currency, &raffle.reward, &user.pubkey());
+        self.bank.send(&[ix], &[user]).await
+    }
+
+    pub async fn close_raffle(&mut self, raffle: &TestRaffle) -> Result<(), BanksClientError> {
+        let creator = self.creator.pubkey();
+        let ix = instructions::close_raffle(&raffle.address, &self.currency, &creator, &creator);
+        self.bank.send(&[ix], &[&self.creator]).await
+    }
+
+    pub async fn crank(&mut self, raffle: &TestRaffle) {
+        let ix = instructions::crank_status(&raffle.address);
+        self.bank.send(&[ix], &[]).await.unwrap();
+    }
+
+    pub async fn balance(&mut self, owner: &Pubkey) -> u64 {
+        let address = pda::token_account(owner, &self.currency);
+        self.bank.token_balance(&address).await
+    }
+
+    pub async fn proceeds_balance(&mut self, raffle: &TestRaffle) -> u64 {
+        self.bank.token_balance(&pda::proceeds(&raffle.address).0).await
+    }
+}
+
+
+// Event CPIs of every test in the binary, without the event instruction tag
+static EVENTS: Mutex<Vec<Vec<u8>>> = Mutex::new(Vec::new());
+
+fn entry<'info>(program_id: &Pubkey, accounts: &'info [AccountInfo<'info>], data: &[u8]) -> ProgramResult {
+    if let Some(event) = data.strip_prefix(EVENT_IX_TAG_LE) {
+        EVENTS.lock().unwrap().push(event.to_vec());
+    }
+
+    shitcoin_raffle::entry(program_id, accounts, data)
+}
+
+// Emitted events of type T matching the filter, oldest first. Tests run in parallel and failed
+// transactions are recorded too, so filter by an account the test owns
+pub fn events<T: AnchorDeserialize + Discriminator>(filter: impl Fn(&T) -> bool) -> Vec<T> {
+    EVENTS
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|data| data.strip_prefix(&T::discriminator()[..]))
+        .filter_map(|body| T::try_from_slice(body).ok())
+        .filter(|event| filter(event))
+        .collect()
+}
+
+
+// Upgradeable loader program data naming the upgrade authority
+fn program_data(upgrade_authority: &Pubkey) -> Account {
+    let state = UpgradeableLoaderState::ProgramData {
+        slot: 0,
+        upgrade_authority_address: Some(*upgrade_authority),
+    };
+
+    Account {
+        lamports: LAMPORTS_PER_SOL,
+        data: bincode::serialize(&state).unwrap(),
+        owner: bpf_loader_upgradeable::ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
@@ -0,0 +1,173 @@
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use shitcoin_raffle_client::{instructions, RaffleError, Role};
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::signature::{Keypair, Signer};
+
+use common::*;
+
+
+#[tokio::test]
+async fn init_admin_sets_the_admin() {
+    let mut test = Test::new().await;
+
+    let admin_settings = test.admin_settings().await;
+    assert_eq!(admin_settings.admin, test.admin.pubkey());
+    assert_eq!(admin_settings.pending_admin, None);
+    assert_eq!(admin_settings.get_treasury(), test.admin.pubkey());
+    assert!(!admin_settings.paused);
+}
+
+#[tokio::test]
+async fn proposed_admin_takes_over_once_accepted() {
+    let mut test = Test::new().await;
+    let new_admin = test.user().await;
+    let other = test.user().await;
+
+    let ix = instructions::propose_admin(&other.pubkey(), &other.pubkey());
+    assert_error(test.bank.send(&[ix], &[&other]).await, ErrorCode::ConstraintRaw);
+
+    let ix = instructions::propose_admin(&test.admin.pubkey(), &new_admin.pubkey());
+    test.bank.send(&[ix], &[&test.admin]).await.unwrap();
+    assert_eq!(test.admin_settings().await.pending_admin, Some(new_admin.pubkey()));
+
+    let ix = instructions::accept_admin(&other.pubkey());
+    assert_error(test.bank.send(&[ix], &[&other]).await, RaffleError::AdminNotProposed);
+
+    let ix = instructions::accept_admin(&new_admin.pubkey());
+    test.bank.send(&[ix], &[&new_admin]).await.unwrap();
+
+    let admin_settings = test.admin_settings().await;
+    assert_eq!(admin_settings.admin, new_admin.pubkey());
+    assert_eq!(admin_settings.pending_admin, None);
+}
+
+#[tokio::test]
+async fn cancelled_proposal_can_not_be_accepted() {
+    let mut test = Test::new().await;
+    let new_admin = test.user().await;
+
+    let ix = instructions::propose_admin(&test.admin.pubkey(), &new_admin.pubkey());
+    test.bank.send(&[ix], &[&test.admin]).await.unwrap();
+
+    let ix = instructions::cancel_admin(&test.admin.pubkey());
+    test.bank.send(&[ix], &[&test.admin]).await.unwrap();
+    assert_eq!(test.admin_settings().await.pending_admin, None);
+
+    let ix = instructions::accept_admin(&new_admin.pubkey());
+    assert_error(test.bank.send(&[ix], &[&new_admin]).await, RaffleError::AdminNotProposed);
+}
+
+#[tokio::test]
+async fn deprecated_set_admin_still_hands_over_the_admin() {
+    let mut test = Test::new().await;
+    let new_admin = test.user().await;
+    let other = test.user().await;
+
+    let ix = instructions::propose_admin(&test.admin.pubkey(), &other.pubkey());
+    test.bank.send(&[ix], &[&test.admin]).await.unwrap();
+
+    let ix = instructions::set_admin(&new_admin.pubkey(), &new_admin.pubkey());
+    assert_error(test.bank.send(&[ix], &[&new_admin]).await, ErrorCode::ConstraintRaw);
+
+    let ix = instructions::set_admin(&test.admin.pubkey(), &new_admin.pubkey());
+    test.bank.send(&[ix], &[&test.admin]).await.unwrap();
+
+    let admin_settings = test.admin_settings().await;
+    assert_eq!(admin_settings.admin, new_admin.pubkey());
+    assert_eq!(admin_settings.pending_admin, None);
+}
+
+#[tokio::test]
+async fn creation_fee_is_paid_to_the_treasury_unless_the_creator_has_the_role() {
+    let mut test = Test::new().await;
+
+    let ix = instructions::set_creation_fee(&test.admin.pubkey(), LAMPORTS_PER_SOL);
+    test.bank.send(&[ix], &[&test.admin]).await.unwrap();
+    assert_eq!(test.admin_settings().await.creation_fee, LAMPORTS_PER_SOL);
+
+    let treasury = test.admin.pubkey();
+    let before = test.bank.lamports(&treasury).await;
+    test.create_raffle(&params()).await;
+    assert_eq!(test.bank.lamports(&treasury).await, before + LAMPORTS_PER_SOL);
+
+    let ix = instructions::grant_role(&test.admin.pubkey(), Role::Creator, &test.creator.pubkey());
+    test.bank.send(&[ix], &[&test.admin]).await.unwrap();
+
+    let before = test.bank.lamports(&treasury).await;
+    test.create_raffle(&params()).await;
+    assert_eq!(test.bank.lamports(&treasury).await, before);
+}
+
+#[tokio::test]
+async fn set_max_extension_requires_the_upgrade_authority() {
+    let mut test = Test::new().await;
+    let other = test.user().await;
+
+    let ix = instructions::set_max_extension(&other.pubkey(), 3_600);
+    assert_error(test.bank.send(&[ix], &[&other]).await, ErrorCode::ConstraintRaw);
+
+    let ix = instructions::set_max_extension(&test.admin.pubkey(), -1);
+    assert_error(test.bank.send(&[ix], &[&test.admin]).await, RaffleError::InvalidMaxExtension);
+
+    let ix = instructions::set_max_extension(&test.admin.pubkey(), 3_600);
+    test.bank.send(&[ix], &[&test.admin]).await.unwrap();
+    assert_eq!(test.admin_settings().await.max_extension, 3_600);
+}
+
+#[tokio::test]
+async fn granted_role_is_honoured_until_revoked() {
+    let mut test = Test::new().await;
+    let drawer = Keypair::new();
+    test.bank.fund(&drawer.pubkey(), LAMPORTS_PER_SOL).await;
+
+    let raffle = test.create_raffle(&params()).await;
+    let alice = test.user().await;
+    test.bank.warp(START).await;
+    test.enter(&raffle, &alice, 3).await.unwrap();
+    test.bank.warp(END).await;
+
+    let ix = instructions::set_reward(&raffle.address, &drawer.pubkey(), &alice.pubkey(), 1);
+    assert_error(test.bank.send(&[ix], &[&drawer]).await, ErrorCode::ConstraintRaw);
+
+    let ix = instructions::grant_role(&test.admin.pubkey(), Role::Drawer, &drawer.pubkey());
+    test.bank.send(&[ix], &[&test.admin]).await.unwrap();
+    assert_eq!(test.admin_settings().await.drawer, drawer.pubkey());
+
+    let ix = instructions::revoke_role(&test.admin.pubkey(), Role::Drawer);
+    test.bank.send(&[ix], &[&test.admin]).await.unwrap();
+    assert_eq!(test.admin_settings().await.drawer, Default::default());
+
+    let ix = instructions::set_reward(&raffle.address, &drawer.pubkey(), &alice.pubkey(), 1);
+    assert_error(test.bank.send(&[ix], &[&drawer]).await, ErrorCode::ConstraintRaw);
+
+    let ix = instructions::grant_role(&test.admin.pubkey(), Role::Drawer, &drawer.pubkey());
+    test.bank.send(&[ix], &[&test.admin]).await.unwrap();
+
+    let ix = instructions::set_reward(&raffle.address, &drawer.pubkey(), &alice.pubkey(), 1);
+    test.bank.send(&[ix], &[&drawer]).await.unwrap();
+    assert_eq!(test.raffle(&raffle).await.rewards_awarded, 1);
+}
+
+#[tokio::test]
+async fn paused_program_rejects_entries() {
+    let mut test = Test::new().await;
+    let raffle = test.create_raffle(&params()).await;
+    let alice = test.user().await;
+    test.bank.warp(START).await;
+
+    let ix = instructions::set_paused(&alice.pubkey(), true);
+    assert_error(test.bank.send(&[ix], &[&alice]).await, ErrorCode::ConstraintRaw);
+
+    let ix = instructions::set_paused(&test.admin.pubkey(), true);
+    test.bank.send(&[ix], &[&test.admin]).await.unwrap();
+    assert!(test.admin_settings().await.paused);
+
+    assert_error(test.enter(&raffle, &alice, 1).await, RaffleError::ProgramPaused);
+
+    let ix = instructions::set_paused(&test.admin.pubkey(), false);
+    test.bank.send(&[ix], &[&test.admin]).await.unwrap();
+
+    test.enter(&raffle, &alice, 1).await.unwrap();
+}
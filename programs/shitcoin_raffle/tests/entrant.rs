@@ -0,0 +1,185 @@
+mod common;
+
+use shitcoin_raffle_client::instructions::{self, RaffleParams};
+use shitcoin_raffle_client::{pda, Entrant, RaffleError, Referrer};
+use solana_sdk::signature::{Keypair, Signer};
+
+use common::*;
+
+
+/*
+ * Entrants
+ */
+
+#[tokio::test]
+async fn entrant_without_tickets_can_be_cancelled() {
+    let mut test = Test::new().await;
+    let raffle = test.create_raffle(&params()).await;
+    let alice = test.user().await;
+    let bob = test.user().await;
+    test.bank.warp(START).await;
+
+    let ix = instructions::init_entrant(&raffle.address, &alice.pubkey());
+    test.bank.send(&[ix], &[&alice]).await.unwrap();
+
+    let entrant: Entrant = test.bank.account(&pda::entrant(&raffle.address, &alice.pubkey()).0).await;
+    assert_eq!(entrant.user, alice.pubkey());
+    assert_eq!(entrant.raffle, raffle.address);
+    assert_eq!(entrant.tickets, 0);
+
+    let ix = instructions::cancel_entrant(&raffle.address, &alice.pubkey());
+    test.bank.send(&[ix], &[&alice]).await.unwrap();
+    assert!(!test.bank.exists(&pda::entrant(&raffle.address, &alice.pubkey()).0).await);
+
+    test.enter(&raffle, &bob, 1).await.unwrap();
+    let ix = instructions::cancel_entrant(&raffle.address, &bob.pubkey());
+    assert_error(test.bank.send(&[ix], &[&bob]).await, RaffleError::EntrantHasTickets);
+}
+
+#[tokio::test]
+async fn entrant_is_closed_only_after_the_draw() {
+    let mut test = Test::new().await;
+    let raffle = test.create_raffle(&params()).await;
+    let alice = test.user().await;
+    test.bank.warp(START).await;
+    test.enter(&raffle, &alice, 3).await.unwrap();
+
+    assert_error(test.close_entrant(&raffle, &alice).await, RaffleError::RaffleStillActive);
+
+    test.bank.warp(END).await;
+    assert_error(test.close_entrant(&raffle, &alice).await, RaffleError::RaffleRewardsNotSet);
+
+    test.set_reward(&raffle, &alice.pubkey(), 2).await.unwrap();
+    test.close_entrant(&raffle, &alice).await.unwrap();
+    assert_eq!(test.balance(&alice.pubkey()).await, BALANCE - 3 * PRICE + PRICE - FEE);
+    assert_eq!(test.bank.token_balance(&pda::token_account(&alice.pubkey(), &raffle.reward)).await, 2 * REWARDS_AMOUNT);
+
+    let state = test.raffle(&raffle).await;
+    assert_eq!(state.rewards_claimed, 2);
+    assert_eq!(state.tickets_refunded, 1);
+}
+
+#[tokio::test]
+async fn winners_can_not_claim_their_reward_while_paused() {
+    let mut test = Test::new().await;
+    let raffle = test.create_raffle(&params()).await;
+    let alice = test.user().await;
+    let bob = test.user().await;
+    test.bank.warp(START).await;
+    test.enter(&raffle, &alice, 1).await.unwrap();
+    test.enter(&raffle, &bob, 2).await.unwrap();
+
+    test.bank.warp(END).await;
+    test.set_reward(&raffle, &alice.pubkey(), 1).await.unwrap();
+    test.set_reward(&raffle, &bob.pubkey(), 1).await.unwrap();
+
+    let ix = instructions::set_raffle_paused(&raffle.address, &test.admin.pubkey(), true, false);
+    test.bank.send(&[ix], &[&test.admin]).await.unwrap();
+
+    assert_error(test.close_entrant(&raffle, &alice).await, RaffleError::RafflePaused);
+
+    test.close_entrant(&raffle, &bob).await.unwrap();
+    assert_eq!(test.balance(&bob.pubkey()).await, BALANCE - 2 * PRICE + PRICE - FEE);
+    let entrant: Entrant = test.bank.account(&pda::entrant(&raffle.address, &bob.pubkey()).0).await;
+    assert_eq!((entrant.tickets, entrant.rewards), (1, 1));
+    assert_error(test.close_entrant(&raffle, &bob).await, RaffleError::RafflePaused);
+
+    let ix = instructions::set_raffle_paused(&raffle.address, &test.admin.pubkey(), false, false);
+    test.bank.send(&[ix], &[&test.admin]).await.unwrap();
+
+    test.close_entrant(&raffle, &alice).await.unwrap();
+    test.close_entrant(&raffle, &bob).await.unwrap();
+    assert_eq!(test.balance(&bob.pubkey()).await, BALANCE - 2 * PRICE + PRICE - FEE);
+    assert_eq!(test.bank.token_balance(&pda::token_account(&bob.pubkey(), &raffle.reward)).await, REWARDS_AMOUNT);
+    assert!(!test.bank.exists(&pda::entrant(&raffle.address, &bob.pubkey()).0).await);
+
+    let state = test.raffle(&raffle).await;
+    assert_eq!(state.tickets_refunded, 1);
+    assert_eq!(state.rewards_claimed, 2);
+}
+
+#[tokio::test]
+async fn entrants_are_settled_in_a_batch() {
+    let mut test = Test::new().await;
+    let raffle = test.create_raffle(&params()).await;
+    let users = [test.user().await, test.user().await, test.user().await];
+    test.bank.warp(START).await;
+    for user in &users {
+        test.enter(&raffle, user, 2).await.unwrap();
+    }
+
+    test.bank.warp(END).await;
+    test.set_reward(&raffle, &users[0].pubkey(), 2).await.unwrap();
+
+    let wallets: Vec<_> = users.iter().map(Keypair::pubkey).collect();
+    let payer = test.bank.payer();
+
+    let mut ix = instructions::settle_entrants(&raffle.address, &test.currency, &raffle.reward, &payer, &wallets);
+    ix.accounts.pop();
+    assert_error(test.bank.send(&[ix], &[]).await, RaffleError::InvalidSettlementAccounts);
+
+    let mut ix = instructions::settle_entrants(&raffle.address, &test.currency, &raffle.reward, &payer, &wallets[..1]);
+    let user_index = ix.accounts.len() - 3;
+    ix.accounts[user_index].pubkey = wallets[1];
+    assert_error(test.bank.send(&[ix], &[]).await, RaffleError::InvalidSettlementAccounts);
+
+    let ix = instructions::settle_entrants(&raffle.address, &test.currency, &raffle.reward, &payer, &wallets);
+    test.bank.send(&[ix], &[]).await.unwrap();
+
+    assert_eq!(test.balance(&wallets[0]).await, BALANCE - 2 * PRICE);
+    assert_eq!(test.balance(&wallets[1]).await, BALANCE - 2 * FEE);
+    assert_eq!(test.balance(&wallets[2]).await, BALANCE - 2 * FEE);
+    assert_eq!(test.bank.token_balance(&pda::token_account(&wallets[0], &raffle.reward)).await, 2 * REWARDS_AMOUNT);
+    for wallet in &wallets {
+        assert!(!test.bank.exists(&pda::entrant(&raffle.address, wallet).0).await);
+    }
+
+    test.claim_proceeds(&raffle).await.unwrap();
+    assert_eq!(test.proceeds_balance(&raffle).await, 0);
+    test.close_raffle(&raffle).await.unwrap();
+}
+
+
+/*
+ * Referrers
+ */
+
+#[tokio::test]
+async fn referrer_collects_its_share_of_the_fees() {
+    let mut test = Test::new().await;
+    let raffle = test.create_raffle(&RaffleParams { referral_share: Some(2_000), ..params() }).await;
+    let alice = test.user().await;
+    let dave = test.user().await;
+    let referral = 5 * FEE * 2_000 / 10_000;
+    test.bank.warp(START).await;
+
+    for user in [&alice, &dave] {
+        let ix = instructions::init_referrer(&raffle.address, &user.pubkey());
+        test.bank.send(&[ix], &[user]).await.unwrap();
+    }
+
+    let ix = instructions::init_entrant(&raffle.address, &alice.pubkey());
+    test.bank.send(&[ix], &[&alice]).await.unwrap();
+    test.bank.create_token_account(&alice.pubkey(), &raffle.reward).await;
+
+    assert_error(test.buy(&raffle, &alice, Some(&alice.pubkey()), 5).await, RaffleError::SelfReferral);
+    test.buy(&raffle, &alice, Some(&dave.pubkey()), 5).await.unwrap();
+
+    let referrer: Referrer = test.bank.account(&pda::referrer(&raffle.address, &dave.pubkey()).0).await;
+    assert_eq!(referrer.tickets, 5);
+    assert_eq!(referrer.accrued, referral);
+    assert_eq!(test.raffle(&raffle).await.referral_accrued, referral);
+
+    let ix = instructions::close_referrer(&raffle.address, &test.currency, &dave.pubkey());
+    assert_error(test.bank.send(&[ix], &[&dave]).await, RaffleError::RaffleStillActive);
+
+    test.bank.warp(END).await;
+    let ix = instructions::close_referrer(&raffle.address, &test.currency, &dave.pubkey());
+    test.bank.send(&[ix], &[&dave]).await.unwrap();
+    assert_eq!(test.balance(&dave.pubkey()).await, BALANCE + referral);
+    assert!(!test.bank.exists(&pda::referrer(&raffle.address, &dave.pubkey()).0).await);
+
+    let ix = instructions::close_referrer(&raffle.address, &test.currency, &alice.pubkey());
+    test.bank.send(&[ix], &[&alice]).await.unwrap();
+    assert_eq!(test.raffle(&raffle).await.referral_claimed, referral);
+}
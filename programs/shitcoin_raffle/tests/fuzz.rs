@@ -0,0 +1,279 @@
+mod common;
+
+use std::cmp;
+
+use proptest::prelude::*;
+use proptest::test_runner::TestCaseError;
+use shitcoin_raffle_client::instructions::{self, RaffleParams};
+use shitcoin_raffle_client::{pda, Entrant, RaffleStatus};
+use solana_program_test::BanksClientError;
+use solana_sdk::signature::{Keypair, Signer};
+
+use common::*;
+
+
+/*
+ * Stateful fuzzing of instruction sequences
+ *
+ * Random sequences of entrant, draw and settlement instructions are sent to the bank with random
+ * clock warps in between, failing instructions included. Rewards are set the way a drawer sets
+ * them, every winner at once and up to the rewards target, or beyond that to check the program
+ * rejects it. A model of what every user paid in and got back is kept next to it and the
+ * invariants are checked after every step. The sequence is then drained, drawing and claiming
+ * whatever is left, and the raffle has to close.
+ *
+ * Failing sequences are shrunk and saved to tests/fuzz.proptest-regressions, they are replayed
+ * first on the next run. Longer runs take PROPTEST_CASES, e.g.
+ * PROPTEST_CASES=1000 cargo test --release --test fuzz
+ */
+
+// Number of users sending instructions
+const USERS: usize = 4;
+
+// Maximum number of steps in a sequence
+const MAX_STEPS: usize = 48;
+
+// Default number of sequences per run
+const CASES: u32 = 16;
+
+// Ticket supply and per entrant limit of the fuzzed raffle
+const TICKETS: u64 = 12;
+const LIMIT: u64 = 5;
+
+#[derive(Clone, Copy, Debug)]
+enum Step {
+    Warp(i64),
+    InitEntrant(usize),
+    BuyTickets(usize, u64),
+    // Winning tickets beyond what the drawer would award the user
+    SetReward(usize, u64),
+    ClaimProceeds,
+    CloseEntrant(usize),
+    CloseRaffle,
+}
+
+// What a user paid in and got back, tickets and rewards are those of its open entrant
+#[derive(Clone, Copy, Debug, Default)]
+struct UserModel {
+    tickets: u64,
+    rewards: u64,
+    paid: u64,
+    refunded: u64,
+    minted: u64,
+}
+
+struct Fuzz {
+    test: Test,
+    raffle: TestRaffle,
+    users: Vec<Keypair>,
+    models: [UserModel; USERS],
+
+    // Proceeds paid to the creator
+    claimed: u64,
+
+    closed: bool,
+}
+
+
+fn step() -> impl Strategy<Value = Step> {
+    prop_oneof![
+        2 => (1..=300i64).prop_map(Step::Warp),
+        2 => (0..USERS).prop_map(Step::InitEntrant),
+        4 => (0..USERS, 1..=3u64).prop_map(|(user, tickets)| Step::BuyTickets(user, tickets)),
+        3 => (0..USERS, prop_oneof![3 => Just(0u64), 1 => 1..=2u64]).prop_map(|(user, excess)| Step::SetReward(user, excess)),
+        1 => Just(Step::ClaimProceeds),
+        2 => (0..USERS).prop_map(Step::CloseEntrant),
+        1 => Just(Step::CloseRaffle),
+    ]
+}
+
+fn config() -> ProptestConfig {
+    let cases = std::env::var("PROPTEST_CASES").ok().and_then(|cases| cases.parse().ok());
+
+    ProptestConfig { cases: cases.unwrap_or(CASES), ..ProptestConfig::default() }
+}
+
+
+impl Fuzz {
+
+    async fn new() -> Fuzz {
+        let mut test = Test::new().await;
+        let raffle = test.create_raffle(&RaffleParams { tickets: Some(TICKETS), limit: Some(LIMIT), ..params() }).await;
+
+        let mut users = Vec::new();
+        for _ in 0..USERS {
+            let user = test.user().await;
+            test.bank.create_token_account(&user.pubkey(), &raffle.reward).await;
+            users.push(user);
+        }
+
+        Fuzz { test, raffle, users, models: [UserModel::default(); USERS], claimed: 0, closed: false }
+    }
+
+    async fn apply(&mut self, step: Step) -> Result<(), BanksClientError> {
+        let Fuzz { test, raffle, users, models, claimed, closed } = self;
+
+        match step {
+            Step::Warp(seconds) => {
+                let now = test.bank.now();
+                test.bank.warp(now + seconds).await;
+                Ok(())
+            }
+            Step::InitEntrant(user) => {
+                let ix = instructions::init_entrant(&raffle.address, &users[user].pubkey());
+                test.bank.send(&[ix], &[&users[user]]).await
+            }
+            Step::BuyTickets(user, tickets) => {
+                test.buy(raffle, &users[user], None, tickets).await?;
+                models[user].tickets += tickets;
+                models[user].paid += tickets * PRICE;
+                Ok(())
+            }
+            Step::SetReward(user, excess) => {
+                let amount = match *closed {
+                    true => excess,
+                    false => {
+                        let state = test.raffle(raffle).await;
+                        let remaining = state.get_rewards_target() - state.rewards_awarded;
+                        cmp::min(models[user].tickets, remaining) + excess
+                    }
+                };
+                test.set_reward(raffle, &users[user].pubkey(), amount).await?;
+                models[user].rewards = amount;
+                Ok(())
+            }
+            Step::ClaimProceeds => {
+                let proceeds = match *closed {
+                    true => 0,
+                    false => test.raffle(raffle).await.get_authority_proceeds().unwrap(),
+                };
+                test.claim_proceeds(raffle).await?;
+                *claimed += proceeds;
+                Ok(())
+            }
+            Step::CloseEntrant(user) => {
+                test.close_entrant(raffle, &users[user]).await?;
+                let model = &mut models[user];
+                model.refunded += (model.tickets - model.rewards) * (PRICE - FEE);
+                model.minted += model.rewards * REWARDS_AMOUNT;
+                model.tickets = 0;
+                model.rewards = 0;
+                Ok(())
+            }
+            Step::CloseRaffle => {
+                test.close_raffle(raffle).await?;
+                *closed = true;
+                Ok(())
+            }
+        }
+    }
+
+    async fn check(&mut self) -> Result<(), TestCaseError> {
+        let Fuzz { test, raffle, users, models, claimed, closed } = self;
+        let creator = test.creator.pubkey();
+
+        // No entrant is paid twice, and none is left short
+        for (user, model) in users.iter().zip(models.iter()) {
+            prop_assert_eq!(test.balance(&user.pubkey()).await, BALANCE - model.paid + model.refunded);
+            let rewards = test.bank.token_balance(&pda::token_account(&user.pubkey(), &raffle.reward)).await;
+            prop_assert_eq!(rewards, model.minted);
+        }
+        prop_assert_eq!(test.balance(&creator).await, *claimed);
+
+        if *closed {
+            prop_assert!(!test.bank.exists(&raffle.address).await);
+            prop_assert!(!test.bank.exists(&pda::proceeds(&raffle.address).0).await);
+            return Ok(());
+        }
+
+        let state = test.raffle(raffle).await;
+        prop_assert!(state.rewards_awarded <= state.rewards_num);
+        prop_assert!(state.rewards_awarded <= state.tickets_sold);
+        prop_assert_eq!(state.tickets_sold, models.iter().map(|model| model.tickets).sum::<u64>() + state.tickets_refunded + state.rewards_claimed);
+
+        for (user, model) in users.iter().zip(models.iter()) {
+            if model.tickets > 0 {
+                let entrant: Entrant = test.bank.account(&pda::entrant(&raffle.address, &user.pubkey()).0).await;
+                prop_assert_eq!((entrant.tickets, entrant.rewards), (model.tickets, model.rewards));
+            }
+        }
+
+        // The vault holds the refunds of the open entrants and the creator's unclaimed proceeds
+        let refunds: u64 = models.iter().map(|model| (model.tickets - model.rewards) * (PRICE - FEE)).sum();
+        let proceeds = match state.admin_claimed {
+            true => 0,
+            false => state.get_authority_proceeds().unwrap(),
+        };
+        prop_assert_eq!(state.get_liabilities().unwrap(), refunds + proceeds);
+        prop_assert_eq!(test.proceeds_balance(raffle).await, refunds + proceeds);
+
+        let ix = instructions::verify_solvency(&raffle.address);
+        let result = test.bank.send(&[ix], &[]).await;
+        prop_assert!(result.is_ok(), "verify_solvency failed: {:?}", result);
+
+        Ok(())
+    }
+
+    // Draws the rest of the rewards, settles every entrant with tickets and closes the raffle
+    async fn drain(&mut self) -> Result<(), TestCaseError> {
+        if self.closed {
+            return Ok(());
+        }
+
+        let now = cmp::max(self.test.bank.now(), END);
+        self.test.bank.warp(now).await;
+        self.test.crank(&self.raffle).await;
+
+        let state = self.test.raffle(&self.raffle).await;
+        if state.status == RaffleStatus::Ended {
+            for user in 0..USERS {
+                let model = self.models[user];
+                let ended = self.test.raffle(&self.raffle).await.status == RaffleStatus::Ended;
+                if ended && model.tickets > 0 && model.rewards == 0 {
+                    let result = self.apply(Step::SetReward(user, 0)).await;
+                    prop_assert!(result.is_ok(), "set_reward of user {} failed: {:?}", user, result);
+                }
+            }
+            prop_assert_eq!(self.test.raffle(&self.raffle).await.status, RaffleStatus::Drawn);
+        }
+
+        if self.test.raffle(&self.raffle).await.status == RaffleStatus::Drawn {
+            let result = self.apply(Step::ClaimProceeds).await;
+            prop_assert!(result.is_ok(), "claim_proceeds failed: {:?}", result);
+        }
+
+        for user in 0..USERS {
+            if self.models[user].tickets > 0 {
+                let result = self.apply(Step::CloseEntrant(user)).await;
+                prop_assert!(result.is_ok(), "close_entrant of user {} failed: {:?}", user, result);
+            }
+        }
+
+        let result = self.apply(Step::CloseRaffle).await;
+        prop_assert!(result.is_ok(), "close_raffle failed: {:?}", result);
+
+        self.check().await
+    }
+}
+
+
+async fn run(steps: Vec<Step>) -> Result<(), TestCaseError> {
+    let mut fuzz = Fuzz::new().await;
+
+    for step in steps {
+        let _ = fuzz.apply(step).await;
+        fuzz.check().await?;
+    }
+
+    fuzz.drain().await
+}
+
+proptest! {
+    #![proptest_config(config())]
+
+    #[test]
+    fn instruction_sequences_keep_the_raffle_solvent_and_closable(steps in prop::collection::vec(step(), 1..MAX_STEPS)) {
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+        runtime.block_on(run(steps))?;
+    }
+}
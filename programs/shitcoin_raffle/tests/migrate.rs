@@ -0,0 +1,235 @@
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use anchor_lang::AnchorSerialize;
+use shitcoin_raffle::states::{AdminSettingsV0, EntrantV0, RaffleV0};
+use shitcoin_raffle_client::{instructions, pda, AdminSettings, Entrant, Raffle, RaffleError, RaffleStatus};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+use common::*;
+
+
+#[tokio::test]
+async fn legacy_admin_settings_are_migrated_by_the_upgrade_authority() {
+    let mut test = Test::new().await;
+    let (address, bump) = pda::admin_settings();
+    let other = test.user().await;
+
+    let legacy = AdminSettingsV0 { bump, admin: test.admin.pubkey() };
+    test.bank.set_legacy_account::<AdminSettings>(&address, &legacy).await;
+
+    let ix = instructions::migrate_admin_settings(&other.pubkey());
+    assert_error(test.bank.send(&[ix], &[&other]).await, ErrorCode::ConstraintRaw);
+
+    let ix = instructions::migrate_admin_settings(&test.admin.pubkey());
+    test.bank.send(&[ix], &[&test.admin]).await.unwrap();
+
+    let migrated: AdminSettings = test.bank.account(&address).await;
+    assert_eq!((migrated.version, migrated.bump, migrated.admin), (AdminSettings::VERSION, bump, test.admin.pubkey()));
+    assert_eq!(migrated.pending_admin, None);
+    assert_eq!((migrated.creation_fee, migrated.max_extension), (0, 0));
+    assert_eq!(migrated.treasurer, Pubkey::default());
+    assert!(!migrated.paused);
+    assert_eq!(test.bank.data(&address).await.len(), AdminSettings::SPACE);
+
+    let ix = instructions::migrate_admin_settings(&test.admin.pubkey());
+    assert_error(test.bank.send(&[ix], &[&test.admin]).await, RaffleError::AccountAlreadyMigrated);
+}
+
+#[tokio::test]
+async fn legacy_raffle_is_migrated_with_a_derived_status() {
+    let mut test = Test::new().await;
+    let raffle = test.create_raffle(&params()).await;
+    let current = test.raffle(&raffle).await;
+
+    // Start, end, tickets sold, rewards awarded, admin claimed, proceeds left and the expected status
+    let cases = [
+        (START, END, 0, 0, false, 0, RaffleStatus::Pending),
+        (NOW - 100, END, 3, 0, false, 3 * PRICE, RaffleStatus::Active),
+        (NOW - 1_000, NOW - 100, 0, 0, false, 0, RaffleStatus::Failed),
+        (NOW - 1_000, NOW - 100, 3, 1, false, 3 * PRICE, RaffleStatus::Ended),
+        (NOW - 1_000, NOW - 100, 3, 2, false, 3 * PRICE, RaffleStatus::Drawn),
+        (NOW - 1_000, NOW - 100, 3, 2, true, PRICE - FEE, RaffleStatus::Settled),
+    ];
+
+    for (start_timestamp, end_timestamp, tickets_sold, rewards_awarded, admin_claimed, proceeds, status) in cases {
+        let legacy = RaffleV0 {
+            bump: current.bump,
+            seed: current.seed,
+            price: current.price,
+            fee: current.fee,
+            currency: current.currency,
+            rewards_num: current.rewards_num,
+            rewards_amount: current.rewards_amount,
+            reward: current.reward,
+            start_timestamp,
+            end_timestamp,
+            tickets: current.tickets,
+            limit: current.limit,
+            tickets_sold,
+            rewards_awarded,
+            rewards_claimed: 0,
+            admin_claimed,
+        };
+        test.bank.set_legacy_account::<Raffle>(&raffle.address, &legacy).await;
+        test.bank.set_token_balance(&pda::proceeds(&raffle.address).0, proceeds).await;
+
+        let ix = instructions::migrate_raffle(&raffle.address, &test.admin.pubkey());
+        test.bank.send(&[ix], &[&test.admin]).await.unwrap();
+
+        let migrated: Raffle = test.bank.account(&raffle.address).await;
+        assert_eq!(migrated.status, status);
+        assert!(migrated.legacy);
+        assert_eq!(migrated.version, Raffle::VERSION);
+        assert_eq!(migrated.creator, test.admin.pubkey());
+        assert_eq!(migrated.initial_end_timestamp, end_timestamp);
+        assert_eq!((migrated.tickets_sold, migrated.rewards_awarded), (tickets_sold, rewards_awarded));
+        assert_eq!((migrated.entrants, migrated.tickets_refunded, migrated.referral_accrued), (0, 0, 0));
+        assert_eq!(test.bank.data(&raffle.address).await.len(), Raffle::SPACE);
+    }
+
+    let ix = instructions::migrate_raffle(&raffle.address, &test.admin.pubkey());
+    assert_error(test.bank.send(&[ix], &[&test.admin]).await, RaffleError::AccountAlreadyMigrated);
+}
+
+#[tokio::test]
+async fn legacy_raffle_counts_entrants_refunded_before_the_migration() {
+    let mut test = Test::new().await;
+    let raffle = test.create_raffle(&params()).await;
+    let alice = test.user().await;
+    let bob = test.user().await;
+    test.bank.warp(START).await;
+    test.enter(&raffle, &alice, 2).await.unwrap();
+    test.enter(&raffle, &bob, 1).await.unwrap();
+
+    // Bob's losing ticket is refunded and his entrant closed while the raffle is still legacy
+    test.bank.warp(END).await;
+    test.set_reward(&raffle, &alice.pubkey(), 2).await.unwrap();
+    test.claim_proceeds(&raffle).await.unwrap();
+    test.close_entrant(&raffle, &bob).await.unwrap();
+
+    let current = test.raffle(&raffle).await;
+    let legacy = RaffleV0 {
+        bump: current.bump,
+        seed: current.seed,
+        price: current.price,
+        fee: current.fee,
+        currency: current.currency,
+        rewards_num: current.rewards_num,
+        rewards_amount: current.rewards_amount,
+        reward: current.reward,
+        start_timestamp: current.start_timestamp,
+        end_timestamp: current.end_timestamp,
+        tickets: current.tickets,
+        limit: current.limit,
+        tickets_sold: current.tickets_sold,
+        rewards_awarded: current.rewards_awarded,
+        rewards_claimed: current.rewards_claimed,
+        admin_claimed: current.admin_claimed,
+    };
+    test.bank.set_legacy_account::<Raffle>(&raffle.address, &legacy).await;
+
+    let admin = test.admin.pubkey();
+    let ix = instructions::migrate_raffle(&raffle.address, &admin);
+    test.bank.send(&[ix], &[&test.admin]).await.unwrap();
+
+    let migrated = test.raffle(&raffle).await;
+    assert_eq!(migrated.status, RaffleStatus::Settled);
+    assert_eq!(migrated.tickets_refunded, 1);
+
+    let ix = instructions::verify_solvency(&raffle.address);
+    test.bank.send(&[ix], &[]).await.unwrap();
+
+    test.close_entrant(&raffle, &alice).await.unwrap();
+    let ix = instructions::close_raffle(&raffle.address, &test.currency, &admin, &admin);
+    test.bank.send(&[ix], &[&test.admin]).await.unwrap();
+}
+
+#[tokio::test]
+async fn legacy_raffle_with_tickets_sold_is_not_shortened() {
+    let mut test = Test::new().await;
+    let raffle = test.create_raffle(&params()).await;
+    let alice = test.user().await;
+    test.bank.warp(START).await;
+    test.enter(&raffle, &alice, 1).await.unwrap();
+
+    let ix = instructions::set_shorten_opt_in(&raffle.address, &alice.pubkey(), true);
+    test.bank.send(&[ix], &[&alice]).await.unwrap();
+
+    let current = test.raffle(&raffle).await;
+    let legacy = RaffleV0 {
+        bump: current.bump,
+        seed: current.seed,
+        price: current.price,
+        fee: current.fee,
+        currency: current.currency,
+        rewards_num: current.rewards_num,
+        rewards_amount: current.rewards_amount,
+        reward: current.reward,
+        start_timestamp: current.start_timestamp,
+        end_timestamp: current.end_timestamp,
+        tickets: current.tickets,
+        limit: current.limit,
+        tickets_sold: current.tickets_sold,
+        rewards_awarded: 0,
+        rewards_claimed: 0,
+        admin_claimed: false,
+    };
+    test.bank.set_legacy_account::<Raffle>(&raffle.address, &legacy).await;
+
+    let ix = instructions::migrate_raffle(&raffle.address, &test.admin.pubkey());
+    test.bank.send(&[ix], &[&test.admin]).await.unwrap();
+
+    // No entrants are counted, so every entrant agreeing can't be told apart from none agreeing
+    let ix = instructions::set_end_timestamp(&raffle.address, &test.admin.pubkey(), END - 100);
+    assert_error(test.bank.send(&[ix], &[&test.admin]).await, RaffleError::ShorteningNotAllowed);
+
+    let ix = instructions::set_end_timestamp(&raffle.address, &test.admin.pubkey(), END);
+    test.bank.send(&[ix], &[&test.admin]).await.unwrap();
+}
+
+#[tokio::test]
+async fn legacy_entrant_is_migrated() {
+    let mut test = Test::new().await;
+    let raffle = test.create_raffle(&params()).await;
+    let alice = test.user().await;
+    test.bank.warp(START).await;
+    test.enter(&raffle, &alice, 2).await.unwrap();
+
+    let address = pda::entrant(&raffle.address, &alice.pubkey()).0;
+    let entrant: Entrant = test.bank.account(&address).await;
+    let legacy = EntrantV0 {
+        bump: entrant.bump,
+        user: entrant.user,
+        raffle: entrant.raffle,
+        tickets: entrant.tickets,
+        rewards: entrant.rewards,
+    };
+    test.bank.set_legacy_account::<Entrant>(&address, &legacy).await;
+
+    let ix = instructions::migrate_entrant(&address, &test.admin.pubkey());
+    test.bank.send(&[ix], &[&test.admin]).await.unwrap();
+    let migrated: Entrant = test.bank.account(&address).await;
+    assert_eq!(migrated.try_to_vec().unwrap(), entrant.try_to_vec().unwrap());
+    assert_eq!(test.bank.data(&address).await.len(), Entrant::SPACE);
+
+    test.buy(&raffle, &alice, None, 1).await.unwrap();
+    assert_eq!(test.bank.account::<Entrant>(&address).await.tickets, 3);
+}
+
+#[tokio::test]
+async fn migration_rejects_accounts_of_another_type() {
+    let mut test = Test::new().await;
+    let raffle = test.create_raffle(&params()).await;
+    let alice = test.user().await;
+    test.bank.warp(START).await;
+    test.enter(&raffle, &alice, 1).await.unwrap();
+
+    let entrant_address = pda::entrant(&raffle.address, &alice.pubkey()).0;
+    let ix = instructions::migrate_raffle(&entrant_address, &test.admin.pubkey());
+    assert_error(test.bank.send(&[ix], &[&test.admin]).await, RaffleError::InvalidMigrationAccount);
+
+    let ix = instructions::migrate_entrant(&test.currency, &test.admin.pubkey());
+    assert_error(test.bank.send(&[ix], &[&test.admin]).await, RaffleError::InvalidMigrationAccount);
+}
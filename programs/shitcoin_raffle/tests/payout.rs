@@ -0,0 +1,265 @@
+use proptest::prelude::*;
+use shitcoin_raffle::states::{Raffle, RaffleStatus};
+
+
+/*
+ * Property tests of the payout math
+ *
+ * Purchases, draws and claims are replayed against the Raffle methods the instructions use, the
+ * vault being the sum of the ticket prices paid in. Whatever the sequence, the refunds, the
+ * referral rewards and the creator's proceeds have to add up to the vault exactly.
+ */
+
+// Raffle end timestamp, the raffle is active before
+const END: i64 = 1_000;
+
+// Number of distinct entrants in a scenario
+const ENTRANTS: usize = 8;
+
+#[derive(Clone, Debug)]
+struct Purchase {
+    entrant: usize,
+    tickets: u64,
+    referred: bool,
+}
+
+#[derive(Clone, Debug)]
+struct Scenario {
+    price: u64,
+    fee: u64,
+    rewards_num: u64,
+    referral_share: u16,
+    purchases: Vec<Purchase>,
+
+    // Entrants in the order they are drawn, one reward per pick
+    picks: Vec<usize>,
+
+    // Entrants closing before the unclaimed funds are swept
+    early_claims: Vec<bool>,
+}
+
+struct Simulation {
+    raffle: Raffle,
+    tickets: [u64; ENTRANTS],
+    rewards: [u64; ENTRANTS],
+    vault: u64,
+}
+
+
+fn purchase() -> impl Strategy<Value = Purchase> {
+    (0..ENTRANTS, 1..=100u64, any::<bool>())
+        .prop_map(|(entrant, tickets, referred)| Purchase { entrant, tickets, referred })
+}
+
+fn scenario() -> impl Strategy<Value = Scenario> {
+    (1..=1_000_000_000u64, 1..=200u64, 0..=10_000u16)
+        .prop_flat_map(|(price, rewards_num, referral_share)| {
+            (
+                Just(price),
+                0..price,
+                Just(rewards_num),
+                Just(referral_share),
+                prop::collection::vec(purchase(), 0..32),
+                prop::collection::vec(0..ENTRANTS, 0..256),
+                prop::collection::vec(any::<bool>(), ENTRANTS),
+            )
+        })
+        .prop_map(|(price, fee, rewards_num, referral_share, purchases, picks, early_claims)| Scenario {
+            price,
+            fee,
+            rewards_num,
+            referral_share,
+            purchases,
+            picks,
+            early_claims,
+        })
+}
+
+fn raffle(price: u64, fee: u64, rewards_num: u64, referral_share: u16) -> Raffle {
+    Raffle {
+        price,
+        fee,
+        rewards_num,
+        rewards_amount: 1_000,
+        end_timestamp: END,
+        initial_end_timestamp: END,
+        status: RaffleStatus::Active,
+        referral_share,
+        ..Raffle::default()
+    }
+}
+
+
+impl Simulation {
+
+    // Sells the tickets and sets the rewards, mirroring buy_tickets and set_reward
+    fn run(scenario: &Scenario) -> Simulation {
+        let mut simulation = Simulation {
+            raffle: raffle(scenario.price, scenario.fee, scenario.rewards_num, scenario.referral_share),
+            tickets: [0; ENTRANTS],
+            rewards: [0; ENTRANTS],
+            vault: 0,
+        };
+
+        for purchase in &scenario.purchases {
+            simulation.buy(purchase);
+        }
+
+        simulation.raffle.advance_at(END).unwrap();
+        if simulation.raffle.status == RaffleStatus::Ended {
+            simulation.draw(&scenario.picks);
+        }
+
+        simulation
+    }
+
+    fn buy(&mut self, purchase: &Purchase) {
+        let raffle = &mut self.raffle;
+
+        self.vault += raffle.price.checked_mul(purchase.tickets).unwrap();
+        if self.tickets[purchase.entrant] == 0 {
+            raffle.entrants += 1;
+        }
+
+        raffle.tickets_sold += purchase.tickets;
+        self.tickets[purchase.entrant] += purchase.tickets;
+
+        if purchase.referred {
+            let referral_amount = raffle.get_referral_amount(purchase.tickets).unwrap();
+            raffle.accrue_referral(referral_amount).unwrap();
+        }
+    }
+
+    // Picked entrants win one reward per pick, the rest of the target goes to the first entrants
+    // with tickets left
+    fn draw(&mut self, picks: &[usize]) {
+        let target = self.raffle.get_rewards_target();
+        let mut wins = [0u64; ENTRANTS];
+        let mut awarded = 0;
+
+        let fill = (0..ENTRANTS).flat_map(|entrant| std::iter::repeat(entrant).take(self.tickets[entrant] as usize));
+        for entrant in picks.iter().copied().chain(fill) {
+            if awarded == target {
+                break;
+            }
+
+            if wins[entrant] < self.tickets[entrant] {
+                wins[entrant] += 1;
+                awarded += 1;
+            }
+        }
+
+        for entrant in 0..ENTRANTS {
+            if wins[entrant] > 0 {
+                self.raffle.award_rewards(wins[entrant]).unwrap();
+                self.rewards[entrant] = wins[entrant];
+            }
+        }
+
+        self.raffle.advance_at(END).unwrap();
+    }
+
+    // Refund and reward of an entrant, mirroring close_entrant
+    fn close_entrant(&mut self, entrant: usize) -> (u64, u64) {
+        if self.raffle.swept || self.tickets[entrant] == 0 {
+            return (0, 0);
+        }
+
+        let refundable_tickets = self.tickets[entrant] - self.rewards[entrant];
+        let refund = self.raffle.get_refunable_proceeds(refundable_tickets).unwrap();
+        self.raffle.refund_tickets(refundable_tickets).unwrap();
+
+        if self.rewards[entrant] > 0 {
+            self.raffle.claim_rewards(self.rewards[entrant]).unwrap();
+        }
+
+        let reward = self.raffle.get_reward_amount(self.rewards[entrant]).unwrap();
+
+        (refund, reward)
+    }
+
+    fn claim_proceeds(&mut self) -> u64 {
+        let proceeds = self.raffle.get_authority_proceeds().unwrap();
+        self.raffle.admin_claimed = true;
+        self.raffle.transition(RaffleStatus::Settled).unwrap();
+
+        proceeds
+    }
+}
+
+
+proptest! {
+
+    #[test]
+    fn payouts_add_up_to_the_vault(scenario in scenario()) {
+        let mut simulation = Simulation::run(&scenario);
+
+        if simulation.raffle.status == RaffleStatus::Failed {
+            prop_assert_eq!(simulation.vault, 0);
+            return Ok(());
+        }
+
+        prop_assert_eq!(simulation.raffle.status, RaffleStatus::Drawn);
+        prop_assert!(simulation.raffle.rewards_awarded <= simulation.raffle.rewards_num);
+
+        let mut paid = simulation.claim_proceeds();
+        let mut minted = 0;
+        for entrant in 0..ENTRANTS {
+            let (refund, reward) = simulation.close_entrant(entrant);
+            paid += refund;
+            minted += reward;
+        }
+
+        let referrals = simulation.raffle.referral_accrued;
+        simulation.raffle.claim_referral(referrals).unwrap();
+        paid += referrals;
+
+        prop_assert_eq!(paid, simulation.vault);
+        prop_assert_eq!(minted, simulation.raffle.rewards_awarded * simulation.raffle.rewards_amount);
+        prop_assert!(simulation.raffle.assert_closeable().is_ok());
+    }
+
+    #[test]
+    fn sweep_takes_exactly_what_was_left_unclaimed(scenario in scenario()) {
+        let mut simulation = Simulation::run(&scenario);
+        prop_assume!(simulation.raffle.status == RaffleStatus::Drawn);
+
+        let mut paid = 0;
+        for entrant in 0..ENTRANTS {
+            if scenario.early_claims[entrant] {
+                paid += simulation.close_entrant(entrant).0;
+            }
+        }
+
+        paid += simulation.raffle.sweep().unwrap();
+        for entrant in 0..ENTRANTS {
+            prop_assert_eq!(simulation.close_entrant(entrant), (0, 0));
+        }
+
+        paid += simulation.claim_proceeds();
+
+        prop_assert_eq!(paid, simulation.vault);
+        prop_assert!(simulation.raffle.assert_closeable().is_ok());
+    }
+
+    #[test]
+    fn payout_math_never_panics(
+        price in any::<u64>(),
+        fee in any::<u64>(),
+        rewards_amount in any::<u64>(),
+        tickets_sold in any::<u64>(),
+        rewards_awarded in any::<u64>(),
+        referral_accrued in any::<u64>(),
+        tickets in any::<u64>(),
+    ) {
+        let mut raffle = raffle(price, fee, 0, 0);
+        raffle.rewards_amount = rewards_amount;
+        raffle.tickets_sold = tickets_sold;
+        raffle.rewards_awarded = rewards_awarded;
+        raffle.referral_accrued = referral_accrued;
+
+        let _ = raffle.get_refunable_proceeds(tickets);
+        let _ = raffle.get_authority_proceeds();
+        let _ = raffle.get_reward_amount(tickets);
+    }
+}
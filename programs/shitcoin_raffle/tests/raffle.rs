@@ -0,0 +1,612 @@
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use shitcoin_raffle::events::StatusChanged;
+use shitcoin_raffle_client::instructions::{self, RaffleParams};
+use shitcoin_raffle_client::{pda, RaffleError, RaffleStatus};
+use solana_sdk::signature::Signer;
+
+use common::*;
+
+
+/*
+ * Lifecycle
+ */
+
+#[tokio::test]
+async fn raffle_pays_out_every_token_it_took() {
+    let mut test = Test::new().await;
+    let raffle = test.create_raffle(&params()).await;
+    let alice = test.user().await;
+    let bob = test.user().await;
+    assert_eq!(test.raffle(&raffle).await.status, RaffleStatus::Pending);
+
+    test.bank.warp(START).await;
+    test.enter(&raffle, &alice, 3).await.unwrap();
+    test.enter(&raffle, &bob, 2).await.unwrap();
+    assert_eq!(test.proceeds_balance(&raffle).await, 5 * PRICE);
+
+    let state = test.raffle(&raffle).await;
+    assert_eq!(state.status, RaffleStatus::Active);
+    assert_eq!(state.tickets_sold, 5);
+    assert_eq!(state.entrants, 2);
+
+    test.bank.warp(END).await;
+    test.crank(&raffle).await;
+    assert_eq!(test.raffle(&raffle).await.status, RaffleStatus::Ended);
+
+    test.set_reward(&raffle, &alice.pubkey(), 1).await.unwrap();
+    test.set_reward(&raffle, &bob.pubkey(), 1).await.unwrap();
+    assert_eq!(test.raffle(&raffle).await.status, RaffleStatus::Drawn);
+
+    test.claim_proceeds(&raffle).await.unwrap();
+    assert_eq!(test.balance(&test.creator.pubkey()).await, 2 * PRICE + 3 * FEE);
+    assert_eq!(test.raffle(&raffle).await.status, RaffleStatus::Settled);
+
+    test.close_entrant(&raffle, &alice).await.unwrap();
+    test.close_entrant(&raffle, &bob).await.unwrap();
+    assert_eq!(test.balance(&alice.pubkey()).await, BALANCE - 3 * PRICE + 2 * (PRICE - FEE));
+    assert_eq!(test.balance(&bob.pubkey()).await, BALANCE - 2 * PRICE + (PRICE - FEE));
+    assert_eq!(test.bank.token_balance(&pda::token_account(&alice.pubkey(), &raffle.reward)).await, REWARDS_AMOUNT);
+    assert_eq!(test.bank.mint_supply(&raffle.reward).await, 2 * REWARDS_AMOUNT);
+    assert!(!test.bank.exists(&pda::entrant(&raffle.address, &alice.pubkey()).0).await);
+    assert_eq!(test.proceeds_balance(&raffle).await, 0);
+
+    test.close_raffle(&raffle).await.unwrap();
+    assert!(!test.bank.exists(&raffle.address).await);
+    assert!(!test.bank.exists(&pda::proceeds(&raffle.address).0).await);
+}
+
+#[tokio::test]
+async fn crank_status_fails_a_raffle_without_tickets() {
+    let mut test = Test::new().await;
+    let raffle = test.create_raffle(&params()).await;
+
+    test.bank.warp(START).await;
+    test.crank(&raffle).await;
+    assert_eq!(test.raffle(&raffle).await.status, RaffleStatus::Active);
+
+    test.bank.warp(END).await;
+    test.crank(&raffle).await;
+    assert_eq!(test.raffle(&raffle).await.status, RaffleStatus::Failed);
+
+    let ix = instructions::cancel_raffle(&raffle.address, &test.creator.pubkey());
+    assert_error(test.bank.send(&[ix], &[&test.creator]).await, RaffleError::InvalidStatusTransition);
+
+    test.close_raffle(&raffle).await.unwrap();
+    assert!(!test.bank.exists(&raffle.address).await);
+}
+
+#[tokio::test]
+async fn raffle_can_be_cancelled_before_tickets_are_sold() {
+    let mut test = Test::new().await;
+    let cancelled = test.create_raffle(&params()).await;
+    let sold = test.create_raffle(&params()).await;
+    let alice = test.user().await;
+
+    let ix = instructions::cancel_raffle(&cancelled.address, &test.creator.pubkey());
+    test.bank.send(&[ix], &[&test.creator]).await.unwrap();
+    assert_eq!(test.raffle(&cancelled).await.status, RaffleStatus::Cancelled);
+
+    test.close_raffle(&cancelled).await.unwrap();
+    assert!(!test.bank.exists(&cancelled.address).await);
+
+    test.bank.warp(START).await;
+    test.enter(&sold, &alice, 1).await.unwrap();
+
+    let ix = instructions::cancel_raffle(&sold.address, &test.creator.pubkey());
+    assert_error(test.bank.send(&[ix], &[&test.creator]).await, RaffleError::RaffleHasTicketsSold);
+}
+
+
+/*
+ * Parameters
+ */
+
+#[tokio::test]
+async fn init_raffle_rejects_invalid_params() {
+    let mut test = Test::new().await;
+
+    let cases = [
+        (RaffleParams { fee: Some(PRICE), ..params() }, RaffleError::FeeGreaterThanPrice),
+        (RaffleParams { tickets: Some(REWARDS_NUM), ..params() }, RaffleError::RewardsNumGreaterThanTickets),
+        (RaffleParams { limit: Some(0), ..params() }, RaffleError::LimitLessThanOne),
+        (RaffleParams { referral_share: Some(10_001), ..params() }, RaffleError::ReferralShareTooLarge),
+        (RaffleParams { extension_window: Some(-1), ..params() }, RaffleError::InvalidExtension),
+        (RaffleParams { max_auto_extension: Some(-1), ..params() }, RaffleError::InvalidExtension),
+        (RaffleParams { claim_period: Some(-1), ..params() }, RaffleError::InvalidClaimPeriod),
+        (RaffleParams { start_timestamp: END, ..params() }, RaffleError::StartAfterEndTimestamp),
+        (RaffleParams { start_timestamp: NOW - 10, end_timestamp: NOW, ..params() }, RaffleError::EndTimestampAlreadyPassed),
+    ];
+
+    for (params, error) in cases {
+        let (raffle, result) = test.try_create_raffle(&params).await;
+        assert_error(result, error);
+        assert!(!test.bank.exists(&raffle.address).await);
+    }
+}
+
+#[tokio::test]
+async fn raffle_can_be_updated_until_it_starts() {
+    let mut test = Test::new().await;
+    let raffle = test.create_raffle(&params()).await;
+
+    let updated = RaffleParams { price: 2 * PRICE, tickets: Some(10), ..params() };
+    let ix = instructions::update_raffle(&raffle.address, &test.creator.pubkey(), &updated);
+    test.bank.send(&[ix], &[&test.creator]).await.unwrap();
+
+    let state = test.raffle(&raffle).await;
+    assert_eq!(state.price, 2 * PRICE);
+    assert_eq!(state.tickets, Some(10));
+
+    let invalid = RaffleParams { fee: Some(2 * PRICE), ..updated.clone() };
+    let ix = instructions::update_raffle(&raffle.address, &test.creator.pubkey(), &invalid);
+    assert_error(test.bank.send(&[ix], &[&test.creator]).await, RaffleError::FeeGreaterThanPrice);
+
+    test.bank.warp(START).await;
+    let ix = instructions::update_raffle(&raffle.address, &test.creator.pubkey(), &params());
+    assert_error(test.bank.send(&[ix], &[&test.creator]).await, RaffleError::RaffleAlreadyStarted);
+}
+
+#[tokio::test]
+async fn update_with_a_past_start_activates_the_raffle() {
+    let mut test = Test::new().await;
+    let raffle = test.create_raffle(&params()).await;
+
+    let updated = RaffleParams { start_timestamp: NOW - 100, ..params() };
+    let ix = instructions::update_raffle(&raffle.address, &test.creator.pubkey(), &updated);
+    test.bank.send(&[ix], &[&test.creator]).await.unwrap();
+    assert_eq!(test.raffle(&raffle).await.status, RaffleStatus::Active);
+
+    let changes = events::<StatusChanged>(|event| event.raffle == raffle.address);
+    let changes: Vec<_> = changes.iter().map(|event| (event.old_status, event.new_status)).collect();
+    assert_eq!(changes, [(RaffleStatus::Pending, RaffleStatus::Active)]);
+}
+
+
+/*
+ * Ticket sales
+ */
+
+#[tokio::test]
+async fn entries_are_only_accepted_while_active() {
+    let mut test = Test::new().await;
+    let raffle = test.create_raffle(&params()).await;
+    let alice = test.user().await;
+    let bob = test.user().await;
+
+    assert_error(test.enter(&raffle, &alice, 1).await, RaffleError::RaffleNotStarted);
+
+    test.bank.warp(START).await;
+    test.enter(&raffle, &alice, 1).await.unwrap();
+
+    test.bank.warp(END).await;
+    assert_error(test.enter(&raffle, &bob, 1).await, RaffleError::RaffleEnded);
+    assert_error(test.buy(&raffle, &alice, None, 1).await, RaffleError::RaffleEnded);
+}
+
+#[tokio::test]
+async fn ticket_supply_and_limit_are_enforced() {
+    let mut test = Test::new().await;
+    let raffle = test.create_raffle(&RaffleParams { tickets: Some(5), limit: Some(4), ..params() }).await;
+    let alice = test.user().await;
+    let bob = test.user().await;
+    let carol = test.user().await;
+    test.bank.warp(START).await;
+
+    assert_error(test.enter(&raffle, &alice, 5).await, RaffleError::EntrantTicketLimitReached);
+    test.enter(&raffle, &alice, 4).await.unwrap();
+    assert_error(test.buy(&raffle, &alice, None, 1).await, RaffleError::EntrantTicketLimitReached);
+    assert_error(test.enter(&raffle, &bob, 2).await, RaffleError::RaffleTicketsUnavailable);
+    assert_error(test.enter(&raffle, &bob, u64::MAX).await, RaffleError::InvalidCalculation);
+
+    test.enter(&raffle, &bob, 1).await.unwrap();
+    assert_eq!(test.raffle(&raffle).await.status, RaffleStatus::Ended);
+    assert_error(test.enter(&raffle, &carol, 1).await, RaffleError::RaffleSoldOut);
+}
+
+#[tokio::test]
+async fn late_purchase_extends_the_raffle() {
+    let mut test = Test::new().await;
+    let extension = RaffleParams {
+        extension_window: Some(100),
+        extension_duration: Some(60),
+        max_auto_extension: Some(100),
+        ..params()
+    };
+    let raffle = test.create_raffle(&extension).await;
+    let alice = test.user().await;
+
+    test.bank.warp(START).await;
+    test.enter(&raffle, &alice, 1).await.unwrap();
+    assert_eq!(test.raffle(&raffle).await.end_timestamp, END);
+
+    test.bank.warp(END - 50).await;
+    test.buy(&raffle, &alice, None, 1).await.unwrap();
+    assert_eq!(test.raffle(&raffle).await.end_timestamp, END + 60);
+
+    test.bank.warp(END + 30).await;
+    test.buy(&raffle, &alice, None, 1).await.unwrap();
+    assert_eq!(test.raffle(&raffle).await.end_timestamp, END + 100);
+}
+
+#[tokio::test]
+async fn paused_raffle_rejects_purchases_and_can_be_extended_on_resume() {
+    let mut test = Test::new().await;
+    let raffle = test.create_raffle(&params()).await;
+    let alice = test.user().await;
+    test.bank.warp(START).await;
+    test.enter(&raffle, &alice, 1).await.unwrap();
+
+    let ix = instructions::set_raffle_paused(&raffle.address, &alice.pubkey(), true, false);
+    assert_error(test.bank.send(&[ix], &[&alice]).await, anchor_lang::error::ErrorCode::ConstraintRaw);
+
+    let ix = instructions::set_raffle_paused(&raffle.address, &test.admin.pubkey(), true, false);
+    test.bank.send(&[ix], &[&test.admin]).await.unwrap();
+    assert_error(test.buy(&raffle, &alice, None, 1).await, RaffleError::RafflePaused);
+
+    test.bank.warp(START + 20).await;
+    let ix = instructions::set_raffle_paused(&raffle.address, &test.admin.pubkey(), true, false);
+    test.bank.send(&[ix], &[&test.admin]).await.unwrap();
+    assert_eq!(test.raffle(&raffle).await.paused_at, Some(START));
+
+    let ix = instructions::set_max_extension(&test.admin.pubkey(), 100);
+    test.bank.send(&[ix], &[&test.admin]).await.unwrap();
+
+    test.bank.warp(START + 50).await;
+    let ix = instructions::set_raffle_paused(&raffle.address, &test.admin.pubkey(), false, true);
+    test.bank.send(&[ix], &[&test.admin]).await.unwrap();
+
+    let state = test.raffle(&raffle).await;
+    assert!(!state.paused);
+    assert_eq!(state.end_timestamp, END + 50);
+    test.buy(&raffle, &alice, None, 1).await.unwrap();
+}
+
+#[tokio::test]
+async fn resume_extension_is_capped_and_skips_ended_raffles() {
+    let mut test = Test::new().await;
+    let raffle = test.create_raffle(&params()).await;
+    let ended = test.create_raffle(&params()).await;
+    let alice = test.user().await;
+    test.bank.warp(START).await;
+    test.enter(&raffle, &alice, 1).await.unwrap();
+    test.enter(&ended, &alice, 1).await.unwrap();
+
+    let ix = instructions::set_max_extension(&test.admin.pubkey(), 30);
+    test.bank.send(&[ix], &[&test.admin]).await.unwrap();
+
+    let ix = instructions::set_raffle_paused(&raffle.address, &test.admin.pubkey(), true, false);
+    test.bank.send(&[ix], &[&test.admin]).await.unwrap();
+
+    test.bank.warp(START + 50).await;
+    let ix = instructions::set_raffle_paused(&raffle.address, &test.admin.pubkey(), false, true);
+    test.bank.send(&[ix], &[&test.admin]).await.unwrap();
+    assert_eq!(test.raffle(&raffle).await.end_timestamp, END + 30);
+
+    // Paused after its end, so it is ended first and keeps its end timestamp on resume
+    test.bank.warp(END).await;
+    let ix = instructions::set_raffle_paused(&ended.address, &test.admin.pubkey(), true, false);
+    test.bank.send(&[ix], &[&test.admin]).await.unwrap();
+
+    test.bank.warp(END + 20).await;
+    let ix = instructions::set_raffle_paused(&ended.address, &test.admin.pubkey(), false, true);
+    test.bank.send(&[ix], &[&test.admin]).await.unwrap();
+
+    let state = test.raffle(&ended).await;
+    assert_eq!(state.status, RaffleStatus::Ended);
+    assert_eq!(state.end_timestamp, END);
+}
+
+#[tokio::test]
+async fn paused_raffle_is_not_ended_by_the_crank() {
+    let mut test = Test::new().await;
+    let raffle = test.create_raffle(&params()).await;
+    let late = test.create_raffle(&params()).await;
+    let alice = test.user().await;
+    test.bank.warp(START).await;
+    test.enter(&raffle, &alice, 1).await.unwrap();
+    test.enter(&late, &alice, 1).await.unwrap();
+
+    let ix = instructions::set_raffle_paused(&raffle.address, &test.admin.pubkey(), true, false);
+    test.bank.send(&[ix], &[&test.admin]).await.unwrap();
+
+    test.bank.warp(END).await;
+    test.crank(&raffle).await;
+    assert_eq!(test.raffle(&raffle).await.status, RaffleStatus::Active);
+
+    let ix = instructions::set_raffle_paused(&raffle.address, &test.admin.pubkey(), false, false);
+    test.bank.send(&[ix], &[&test.admin]).await.unwrap();
+
+    test.bank.warp(END + 10).await;
+    test.crank(&raffle).await;
+    assert_eq!(test.raffle(&raffle).await.status, RaffleStatus::Ended);
+
+    // A raffle paused past its end is ended before it is paused
+    let ix = instructions::set_raffle_paused(&late.address, &test.admin.pubkey(), true, false);
+    test.bank.send(&[ix], &[&test.admin]).await.unwrap();
+    assert_eq!(test.raffle(&late).await.status, RaffleStatus::Ended);
+}
+
+
+/*
+ * End timestamp
+ */
+
+#[tokio::test]
+async fn end_timestamp_extension_is_capped() {
+    let mut test = Test::new().await;
+    let raffle = test.create_raffle(&params()).await;
+    test.bank.warp(START + 10).await;
+
+    let ix = instructions::set_end_timestamp(&raffle.address, &test.creator.pubkey(), END + 100);
+    assert_error(test.bank.send(&[ix], &[&test.creator]).await, RaffleError::ExtensionTooLarge);
+
+    let ix = instructions::set_end_timestamp(&raffle.address, &test.creator.pubkey(), START + 5);
+    assert_error(test.bank.send(&[ix], &[&test.creator]).await, RaffleError::EndTimestampAlreadyPassed);
+
+    let ix = instructions::set_max_extension(&test.admin.pubkey(), 100);
+    test.bank.send(&[ix], &[&test.admin]).await.unwrap();
+
+    let ix = instructions::set_end_timestamp(&raffle.address, &test.creator.pubkey(), END + 100);
+    test.bank.send(&[ix], &[&test.creator]).await.unwrap();
+    assert_eq!(test.raffle(&raffle).await.end_timestamp, END + 100);
+}
+
+#[tokio::test]
+async fn end_timestamp_is_only_shortened_with_every_entrant_consent() {
+    let mut test = Test::new().await;
+    let raffle = test.create_raffle(&params()).await;
+    let alice = test.user().await;
+    let bob = test.user().await;
+    test.bank.warp(START).await;
+    test.enter(&raffle, &alice, 1).await.unwrap();
+
+    let ix = instructions::init_entrant(&raffle.address, &bob.pubkey());
+    test.bank.send(&[ix], &[&bob]).await.unwrap();
+    let ix = instructions::set_shorten_opt_in(&raffle.address, &bob.pubkey(), true);
+    assert_error(test.bank.send(&[ix], &[&bob]).await, RaffleError::EntrantHasNoTickets);
+
+    let ix = instructions::set_end_timestamp(&raffle.address, &test.creator.pubkey(), END - 100);
+    assert_error(test.bank.send(&[ix], &[&test.creator]).await, RaffleError::ShorteningNotAllowed);
+
+    let ix = instructions::set_shorten_opt_in(&raffle.address, &alice.pubkey(), true);
+    test.bank.send(&[ix], &[&alice]).await.unwrap();
+    assert_eq!(test.raffle(&raffle).await.shorten_opt_ins, 1);
+
+    let ix = instructions::set_end_timestamp(&raffle.address, &test.creator.pubkey(), END - 100);
+    test.bank.send(&[ix], &[&test.creator]).await.unwrap();
+    assert_eq!(test.raffle(&raffle).await.end_timestamp, END - 100);
+}
+
+
+/*
+ * Rewards
+ */
+
+#[tokio::test]
+async fn rewards_are_set_once_per_entrant_within_the_target() {
+    let mut test = Test::new().await;
+    let raffle = test.create_raffle(&params()).await;
+    let alice = test.user().await;
+    let bob = test.user().await;
+    test.bank.warp(START).await;
+    test.enter(&raffle, &alice, 3).await.unwrap();
+    test.enter(&raffle, &bob, 1).await.unwrap();
+
+    assert_error(test.set_reward(&raffle, &alice.pubkey(), 1).await, RaffleError::RaffleStillActive);
+
+    test.bank.warp(END).await;
+    assert_error(test.set_reward(&raffle, &bob.pubkey(), 2).await, RaffleError::RewardsNumGreaterThanTicketsBought);
+    assert_error(test.set_reward(&raffle, &alice.pubkey(), 3).await, RaffleError::RewardsAmountGreaterThanTotal);
+
+    test.set_reward(&raffle, &alice.pubkey(), 1).await.unwrap();
+    assert_error(test.set_reward(&raffle, &alice.pubkey(), 1).await, RaffleError::EntrantAlreadyAwarded);
+
+    test.set_reward(&raffle, &bob.pubkey(), 1).await.unwrap();
+    assert_eq!(test.raffle(&raffle).await.status, RaffleStatus::Drawn);
+    assert_error(test.set_reward(&raffle, &bob.pubkey(), 1).await, RaffleError::InvalidRaffleStatus);
+}
+
+#[tokio::test]
+async fn proceeds_are_claimed_once_after_the_draw() {
+    let mut test = Test::new().await;
+    let raffle = test.create_raffle(&params()).await;
+    let alice = test.user().await;
+    test.bank.warp(START).await;
+    test.enter(&raffle, &alice, 1).await.unwrap();
+
+    test.bank.warp(END).await;
+    assert_error(test.claim_proceeds(&raffle).await, RaffleError::RaffleRewardsNotSet);
+
+    test.set_reward(&raffle, &alice.pubkey(), 1).await.unwrap();
+    test.claim_proceeds(&raffle).await.unwrap();
+    assert_eq!(test.balance(&test.creator.pubkey()).await, PRICE);
+
+    assert_error(test.claim_proceeds(&raffle).await, RaffleError::RaffleAdminAlreadyClaimed);
+}
+
+
+/*
+ * Closing
+ */
+
+#[tokio::test]
+async fn raffle_is_closed_only_once_everyone_is_paid() {
+    let mut test = Test::new().await;
+    let raffle = test.create_raffle(&RaffleParams { referral_share: Some(5_000), ..params() }).await;
+    let alice = test.user().await;
+    let bob = test.user().await;
+    let carol = test.user().await;
+    let dave = test.user().await;
+    test.bank.warp(START).await;
+
+    test.enter(&raffle, &alice, 1).await.unwrap();
+    test.enter(&raffle, &bob, 1).await.unwrap();
+
+    let ix = instructions::init_referrer(&raffle.address, &dave.pubkey());
+    test.bank.send(&[ix], &[&dave]).await.unwrap();
+    test.bank.create_token_account(&carol.pubkey(), &raffle.reward).await;
+    let ix = instructions::init_entrant(&raffle.address, &carol.pubkey());
+    test.bank.send(&[ix], &[&carol]).await.unwrap();
+    test.buy(&raffle, &carol, Some(&dave.pubkey()), 1).await.unwrap();
+
+    test.bank.warp(END).await;
+    test.set_reward(&raffle, &alice.pubkey(), 1).await.unwrap();
+    test.set_reward(&raffle, &bob.pubkey(), 1).await.unwrap();
+
+    assert_error(test.close_raffle(&raffle).await, RaffleError::RaffleAdminNotClaimed);
+    test.claim_proceeds(&raffle).await.unwrap();
+    assert_eq!(test.balance(&test.creator.pubkey()).await, 2 * PRICE + FEE - FEE / 2);
+
+    assert_error(test.close_raffle(&raffle).await, RaffleError::RaffleRewardsNotClaimed);
+    test.close_entrant(&raffle, &alice).await.unwrap();
+    test.close_entrant(&raffle, &bob).await.unwrap();
+
+    assert_error(test.close_raffle(&raffle).await, RaffleError::RaffleRefundsNotClaimed);
+    test.close_entrant(&raffle, &carol).await.unwrap();
+    assert_eq!(test.balance(&carol.pubkey()).await, BALANCE - FEE);
+
+    assert_error(test.close_raffle(&raffle).await, RaffleError::RaffleReferralsNotClaimed);
+    let ix = instructions::close_referrer(&raffle.address, &test.currency, &dave.pubkey());
+    test.bank.send(&[ix], &[&dave]).await.unwrap();
+    assert_eq!(test.balance(&dave.pubkey()).await, BALANCE + FEE / 2);
+
+    test.close_raffle(&raffle).await.unwrap();
+    assert!(!test.bank.exists(&raffle.address).await);
+}
+
+#[tokio::test]
+async fn unclaimed_funds_are_swept_after_the_claim_deadline() {
+    let mut test = Test::new().await;
+    let raffle = test.create_raffle(&RaffleParams { claim_period: Some(100), ..params() }).await;
+    let alice = test.user().await;
+    let bob = test.user().await;
+    let treasury = test.admin.pubkey();
+    test.bank.warp(START).await;
+    test.enter(&raffle, &alice, 2).await.unwrap();
+    test.enter(&raffle, &bob, 1).await.unwrap();
+
+    test.bank.warp(END).await;
+    test.set_reward(&raffle, &alice.pubkey(), 1).await.unwrap();
+    test.set_reward(&raffle, &bob.pubkey(), 1).await.unwrap();
+    assert_eq!(test.raffle(&raffle).await.claim_deadline, Some(END + 100));
+
+    let ix = instructions::sweep_unclaimed(&raffle.address, &test.currency, &treasury, &test.admin.pubkey());
+    assert_error(test.bank.send(&[ix], &[&test.admin]).await, RaffleError::ClaimDeadlineNotPassed);
+
+    test.bank.warp(END + 100).await;
+    let ix = instructions::sweep_unclaimed(&raffle.address, &test.currency, &treasury, &test.admin.pubkey());
+    test.bank.send(&[ix], &[&test.admin]).await.unwrap();
+    assert_eq!(test.balance(&treasury).await, PRICE - FEE);
+
+    let state = test.raffle(&raffle).await;
+    assert!(state.swept);
+    assert_eq!(state.rewards_forfeited, 2);
+
+    let ix = instructions::sweep_unclaimed(&raffle.address, &test.currency, &treasury, &test.admin.pubkey());
+    assert_error(test.bank.send(&[ix], &[&test.admin]).await, RaffleError::RaffleAlreadySwept);
+
+    test.close_entrant(&raffle, &alice).await.unwrap();
+    assert_eq!(test.balance(&alice.pubkey()).await, BALANCE - 2 * PRICE);
+    assert_eq!(test.bank.mint_supply(&raffle.reward).await, 0);
+
+    test.claim_proceeds(&raffle).await.unwrap();
+    test.close_raffle(&raffle).await.unwrap();
+}
+
+#[tokio::test]
+async fn stray_tokens_are_swept_to_the_treasury() {
+    let mut test = Test::new().await;
+    let raffle = test.create_raffle(&params()).await;
+    let alice = test.user().await;
+    let other = test.user().await;
+    let treasury = test.admin.pubkey();
+    let proceeds = pda::proceeds(&raffle.address).0;
+    test.bank.warp(START).await;
+    test.enter(&raffle, &alice, 1).await.unwrap();
+
+    let payer = test.bank.payer();
+    let stray_mint = test.bank.create_mint(&payer).await;
+    let stray = test.bank.create_token_account(&raffle.address, &stray_mint).await;
+    test.bank.mint_to(&stray_mint, &raffle.address, 70).await;
+    test.bank.transfer_tokens(&test.currency, &proceeds, 50).await;
+
+    let ix = instructions::sweep_excess(&raffle.address, &proceeds, &test.currency, &treasury, &other.pubkey());
+    assert_error(test.bank.send(&[ix], &[&other]).await, ErrorCode::ConstraintRaw);
+
+    let ix = instructions::sweep_excess(&raffle.address, &proceeds, &test.currency, &treasury, &test.admin.pubkey());
+    test.bank.send(&[ix], &[&test.admin]).await.unwrap();
+    assert_eq!(test.balance(&treasury).await, 50);
+    assert_eq!(test.proceeds_balance(&raffle).await, PRICE);
+
+    let ix = instructions::sweep_excess(&raffle.address, &stray, &stray_mint, &treasury, &test.admin.pubkey());
+    test.bank.send(&[ix], &[&test.admin]).await.unwrap();
+    assert_eq!(test.bank.token_balance(&pda::token_account(&treasury, &stray_mint)).await, 70);
+    assert_eq!(test.bank.token_balance(&stray).await, 0);
+
+    test.bank.warp(END).await;
+    test.set_reward(&raffle, &alice.pubkey(), 1).await.unwrap();
+    test.claim_proceeds(&raffle).await.unwrap();
+    test.close_entrant(&raffle, &alice).await.unwrap();
+
+    test.bank.transfer_tokens(&test.currency, &proceeds, 5).await;
+    assert!(test.close_raffle(&raffle).await.is_err());
+
+    let ix = instructions::sweep_excess(&raffle.address, &proceeds, &test.currency, &treasury, &test.admin.pubkey());
+    test.bank.send(&[ix], &[&test.admin]).await.unwrap();
+    assert_eq!(test.balance(&treasury).await, 55);
+
+    test.close_raffle(&raffle).await.unwrap();
+}
+
+#[tokio::test]
+async fn sweep_excess_never_takes_what_the_raffle_owes() {
+    let mut test = Test::new().await;
+    let raffle = test.create_raffle(&params()).await;
+    let alice = test.user().await;
+    let treasury = test.admin.pubkey();
+    let proceeds = pda::proceeds(&raffle.address).0;
+    test.bank.warp(START).await;
+    test.enter(&raffle, &alice, 2).await.unwrap();
+
+    let ix = instructions::sweep_excess(&raffle.address, &proceeds, &test.currency, &treasury, &test.admin.pubkey());
+    test.bank.send(&[ix], &[&test.admin]).await.unwrap();
+    assert_eq!(test.proceeds_balance(&raffle).await, 2 * PRICE);
+
+    test.bank.set_token_balance(&proceeds, PRICE).await;
+    let ix = instructions::sweep_excess(&raffle.address, &proceeds, &test.currency, &treasury, &test.admin.pubkey());
+    assert_error(test.bank.send(&[ix], &[&test.admin]).await, RaffleError::VaultInsolvent);
+}
+
+
+/*
+ * Solvency
+ */
+
+#[tokio::test]
+async fn solvency_is_verified_against_the_proceeds_balance() {
+    let mut test = Test::new().await;
+    let raffle = test.create_raffle(&params()).await;
+    let alice = test.user().await;
+    let proceeds = pda::proceeds(&raffle.address).0;
+    test.bank.warp(START).await;
+    test.enter(&raffle, &alice, 3).await.unwrap();
+
+    let ix = instructions::verify_solvency(&raffle.address);
+    test.bank.send(&[ix], &[]).await.unwrap();
+    assert_eq!(test.raffle(&raffle).await.get_liabilities().unwrap(), 3 * PRICE);
+
+    test.bank.transfer_tokens(&test.currency, &proceeds, 50).await;
+    let ix = instructions::verify_solvency(&raffle.address);
+    test.bank.send(&[ix], &[]).await.unwrap();
+
+    test.bank.warp(END).await;
+    test.set_reward(&raffle, &alice.pubkey(), 2).await.unwrap();
+    test.claim_proceeds(&raffle).await.unwrap();
+    assert_eq!(test.raffle(&raffle).await.get_liabilities().unwrap(), PRICE - FEE);
+
+    test.bank.set_token_balance(&proceeds, PRICE - FEE - 1).await;
+    let ix = instructions::verify_solvency(&raffle.address);
+    assert_error(test.bank.send(&[ix], &[]).await, RaffleError::VaultInsolvent);
+}